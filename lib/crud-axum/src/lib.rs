@@ -0,0 +1,190 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use crud_core::entity::{Entity, Filterable};
+use crud_core::error::{CRUDError, CRUDErrorKind, FieldError};
+use crud_core::repository::{
+    CreateRepository, DeleteRepository, Page, PageRepository, ReadRepository, Repository,
+    UpdateRepository,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/// Maximum number of rows listed by a single request.
+const MAX_LIMIT: u32 = 1000;
+
+/// Builds the routes of the CRUD functions of `T`, to be nested under a path
+/// of its own and given the pool as state:
+///
+/// | Route            | Function    | Success                       |
+/// |------------------|-------------|-------------------------------|
+/// | `GET /`          | `read_page` | `200 OK` with the rows        |
+/// | `POST /`         | `create`    | `201 Created` with the row    |
+/// | `GET /{id}`      | `read_one`  | `200 OK` with the row         |
+/// | `PUT /{id}`      | `update`    | `200 OK` with the updated row |
+/// | `DELETE /{id}`   | `delete`    | `204 No Content`              |
+///
+/// Rows are sent and received as JSON. `GET /` takes the `limit` (at most
+/// 1000) and `offset` of the [`Page`], and a filter value for any field, as
+/// in `?name=Debbie&limit=10`. The body of `PUT /{id}` must have the key of
+/// the path. Errors are returned as an [`ApiError`].
+///
+/// Keys are read from a single path segment, so structs with a composite key
+/// are not supported. Fields managed by the database, such as an
+/// autoincrementing key, can be marked `#[serde(default)]` so that they can
+/// be left out of the body of `POST /`.
+///
+/// ```rust,ignore
+/// let app = Router::new()
+///     .nest("/users", derive_crud::axum::router::<User>())
+///     .with_state(pool);
+/// ```
+pub fn router<T>() -> Router<SqlitePool>
+where
+    T: Filterable + Serialize + DeserializeOwned + Send + Sync + 'static,
+    T::Id: DeserializeOwned + PartialEq + Send + 'static,
+    T::Filter: DeserializeOwned + 'static,
+    SqlitePool: Repository<T> + PageRepository<T>,
+{
+    Router::new()
+        .route("/", get(list::<T>).post(create::<T>))
+        .route("/{id}", get(read::<T>).put(update::<T>).delete(delete::<T>))
+}
+
+/// Error returned by the routes of [`router`], with the status of the kind
+/// of the error and a JSON body such as `{"error": "no row matched the id"}`.
+///
+/// Validation errors also list the failed checks in `fields`, and the
+/// messages of database errors are not sent to the client.
+#[derive(Debug)]
+pub struct ApiError(pub CRUDError);
+
+impl From<CRUDError> for ApiError {
+    fn from(error: CRUDError) -> Self {
+        ApiError(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = status(self.0.kind());
+        let message = match self.0.kind() {
+            CRUDErrorKind::Database => "database error".to_string(),
+            _ => self.0.to_string(),
+        };
+
+        let mut body = serde_json::json!({ "error": message });
+        if !self.0.field_errors().is_empty() {
+            body["fields"] = self
+                .0
+                .field_errors()
+                .iter()
+                .map(|error| {
+                    serde_json::json!({
+                        "field": error.field,
+                        "rule": error.rule,
+                        "message": error.message,
+                    })
+                })
+                .collect();
+        }
+
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Returns the HTTP status of an error of `kind`.
+pub fn status(kind: CRUDErrorKind) -> StatusCode {
+    match kind {
+        CRUDErrorKind::NotFound => StatusCode::NOT_FOUND,
+        CRUDErrorKind::Conflict => StatusCode::CONFLICT,
+        CRUDErrorKind::Validation => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Deserialize)]
+struct PageQuery {
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+async fn list<T>(
+    State(pool): State<SqlitePool>,
+    Query(page): Query<PageQuery>,
+    Query(filter): Query<T::Filter>,
+) -> Result<Json<Vec<T>>, ApiError>
+where
+    T: Filterable,
+    SqlitePool: PageRepository<T>,
+{
+    let default = Page::default();
+    let page = Page {
+        limit: page.limit.unwrap_or(default.limit).min(MAX_LIMIT),
+        offset: page.offset.unwrap_or(default.offset),
+    };
+
+    Ok(Json(pool.read_page(&filter, page).await?))
+}
+
+async fn create<T>(
+    State(pool): State<SqlitePool>,
+    Json(item): Json<T>,
+) -> Result<(StatusCode, Json<T>), ApiError>
+where
+    T: Entity,
+    SqlitePool: Repository<T>,
+{
+    Ok((StatusCode::CREATED, Json(pool.create(item).await?)))
+}
+
+async fn read<T>(State(pool): State<SqlitePool>, Path(id): Path<T::Id>) -> Result<Json<T>, ApiError>
+where
+    T: Entity,
+    SqlitePool: Repository<T>,
+{
+    Ok(Json(pool.read_one(id).await?))
+}
+
+async fn update<T>(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<T::Id>,
+    Json(mut item): Json<T>,
+) -> Result<Json<T>, ApiError>
+where
+    T: Entity,
+    T::Id: PartialEq,
+    SqlitePool: Repository<T>,
+{
+    if item.id() != id {
+        return Err(CRUDError::validation(vec![FieldError {
+            field: T::ID_COLUMNS.first().copied().unwrap_or("id"),
+            rule: "path",
+            message: "must match the id of the path".to_string(),
+        }])
+        .into());
+    }
+
+    // A missing row fails the update with `NotFound`, answered with 404.
+    pool.update(&mut item).await?;
+    Ok(Json(item))
+}
+
+async fn delete<T>(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<T::Id>,
+) -> Result<StatusCode, ApiError>
+where
+    T: Entity,
+    SqlitePool: Repository<T>,
+{
+    // The row is read for its key. A row deleted in between is reported as
+    // missing by the delete itself.
+    let item = pool.read_one(id).await?;
+    pool.delete(&item).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,112 @@
+use sqlx::SqliteConnection;
+
+use crate::error::CRUDError;
+
+/// Context of an audited operation, passed to the `_audited` functions
+/// generated with `#[crud_audit]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditContext {
+    /// Who made the change, stored in the `actor` column.
+    pub actor: Option<String>,
+}
+
+impl AuditContext {
+    /// Returns the context of a change made by `actor`.
+    pub fn actor(actor: impl Into<String>) -> AuditContext {
+        AuditContext {
+            actor: Some(actor.into()),
+        }
+    }
+}
+
+/// Change written to the audit table by [`record`].
+pub struct AuditEntry<'a> {
+    /// Table of the changed row.
+    pub table: &'a str,
+    /// Columns of the key of the row.
+    pub id_columns: &'a [&'a str],
+    /// `create`, `update` or `delete`.
+    pub operation: &'a str,
+    /// Row as a JSON object before the change, unless it is created.
+    pub before: Option<String>,
+    /// Row as a JSON object after the change, unless it is deleted.
+    pub after: Option<String>,
+    pub context: &'a AuditContext,
+}
+
+/// Returns the key of a row read as a JSON object, as text, or as a JSON
+/// array for composite keys.
+pub(crate) fn entity_id(
+    row: &serde_json::Map<String, serde_json::Value>,
+    id_columns: &[&str],
+) -> String {
+    let id_values: Vec<_> = id_columns
+        .iter()
+        .map(|column| row.get(*column).cloned().unwrap_or_default())
+        .collect();
+    match id_values.as_slice() {
+        [serde_json::Value::String(id)] => id.clone(),
+        [id] => id.to_string(),
+        ids => serde_json::Value::Array(ids.to_vec()).to_string(),
+    }
+}
+
+/// Inserts a row for `entry` in `audit_table`, on the connection of the
+/// change. Called by the functions generated with `#[crud_audit]`.
+///
+/// The key of the row is stored as text, or as a JSON array for composite
+/// keys. An update only keeps the fields it changed in `before` and `after`.
+pub async fn record(
+    connection: &mut SqliteConnection,
+    audit_table: &str,
+    entry: AuditEntry<'_>,
+) -> Result<(), CRUDError> {
+    let parse = |snapshot: Option<&String>| {
+        snapshot
+            .map(|snapshot| {
+                serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(snapshot)
+            })
+            .transpose()
+            .map_err(|e| CRUDError::new(e.to_string()))
+    };
+    let mut before = parse(entry.before.as_ref())?;
+    let mut after = parse(entry.after.as_ref())?;
+
+    let Some(row) = after.as_ref().or(before.as_ref()) else {
+        return Err(CRUDError::new(
+            "an audit entry needs a row before or after the change",
+        ));
+    };
+    let entity_id = entity_id(row, entry.id_columns);
+
+    if let (Some(before), Some(after)) = (&mut before, &mut after) {
+        let unchanged: Vec<_> = before
+            .iter()
+            .filter(|(column, value)| after.get(*column) == Some(value))
+            .map(|(column, _)| column.clone())
+            .collect();
+        for column in unchanged {
+            before.remove(&column);
+            after.remove(&column);
+        }
+    }
+    let to_string = |snapshot: Option<serde_json::Map<_, _>>| {
+        snapshot.map(|snapshot| serde_json::Value::Object(snapshot).to_string())
+    };
+
+    sqlx::query(&format!(
+        "INSERT INTO {audit_table} (table_name, entity_id, operation, before, after, actor, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)"
+    ))
+    .bind(entry.table)
+    .bind(entity_id)
+    .bind(entry.operation)
+    .bind(to_string(before))
+    .bind(to_string(after))
+    .bind(&entry.context.actor)
+    .execute(connection)
+    .await
+    .map_err(|e| CRUDError::new(e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::registry::EntityMeta;
+
+/// Table and key metadata of a struct deriving `Read`.
+///
+/// Relationship loaders generated from `#[crud_belongs_to]` use this trait to
+/// reach the key of the referenced struct.
+pub trait Entity {
+    /// Type of the `#[crud_id]` field, or a tuple of them for composite keys.
+    type Id;
+
+    /// Name of the database table given in `#[crud_table]`.
+    const TABLE: &'static str;
+
+    /// Names of the `#[crud_id]` columns, in field declaration order.
+    const ID_COLUMNS: &'static [&'static str];
+
+    /// Description of the struct as seen by `Read`.
+    /// [`registered_entities`](crate::registry::registered_entities) merges it
+    /// with the descriptions submitted by the other derives of the struct.
+    const META: EntityMeta;
+
+    /// Returns the key of this entry.
+    fn id(&self) -> Self::Id;
+}
+
+/// Struct deriving `Read` which can be listed with a filter on its columns,
+/// see [`PageRepository`](crate::repository::PageRepository).
+pub trait Filterable: Entity {
+    /// Generated `{Struct}Filter`, with an optional value for every field.
+    /// Rows match when every value which is set equals their column.
+    type Filter: Default + Send + Sync;
+}
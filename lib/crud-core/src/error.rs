@@ -1,16 +1,86 @@
+/// Category of failure carried by a [`CRUDError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CRUDErrorKind {
+    /// The database reported an error while executing the query.
+    Database,
+    /// No row matched the identifier of the operation.
+    NotFound,
+    /// The row was modified concurrently and its `#[crud_version]` no longer matches.
+    Conflict,
+    /// Fields of the row failed their `#[crud_validate]` checks, listed by
+    /// [`CRUDError::field_errors`].
+    Validation,
+}
+
 /// Error type returned by generated CRUD functions.
 #[derive(Debug)]
-pub struct CRUDError(String);
+pub struct CRUDError {
+    kind: CRUDErrorKind,
+    message: String,
+    field_errors: Vec<FieldError>,
+}
+
+/// Failed `#[crud_validate]` check of a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Name of the field.
+    pub field: &'static str,
+    /// Name of the check, such as `length` or `email`.
+    pub rule: &'static str,
+    /// Description of the failure.
+    pub message: String,
+}
 
 impl CRUDError {
     pub fn new(message: impl Into<String>) -> Self {
-        CRUDError(message.into())
+        CRUDError::with_kind(CRUDErrorKind::Database, message)
+    }
+
+    pub fn with_kind(kind: CRUDErrorKind, message: impl Into<String>) -> Self {
+        CRUDError {
+            kind,
+            message: message.into(),
+            field_errors: Vec::new(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        CRUDError::with_kind(CRUDErrorKind::NotFound, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        CRUDError::with_kind(CRUDErrorKind::Conflict, message)
+    }
+
+    /// Returns an error of kind [`CRUDErrorKind::Validation`] listing the
+    /// failed checks.
+    pub fn validation(field_errors: Vec<FieldError>) -> Self {
+        let message = field_errors
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<_>>()
+            .join(", ");
+        CRUDError {
+            kind: CRUDErrorKind::Validation,
+            message: format!("validation failed: {message}"),
+            field_errors,
+        }
+    }
+
+    pub fn kind(&self) -> CRUDErrorKind {
+        self.kind
+    }
+
+    /// Returns the failed checks of a validation error, in field order.
+    pub fn field_errors(&self) -> &[FieldError] {
+        &self.field_errors
     }
 }
 
 impl std::fmt::Display for CRUDError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        f.write_str(&self.message)
     }
 }
 
@@ -0,0 +1,40 @@
+use async_graphql::{ErrorExtensions, Value};
+
+use crate::error::{CRUDError, CRUDErrorKind};
+
+/// Converts an error of a generated function into a GraphQL error, used by
+/// the resolvers of `#[derive(GraphQL)]`.
+///
+/// The kind of the error is given in the `code` extension, as `NOT_FOUND`,
+/// `CONFLICT`, `VALIDATION` or `DATABASE`. Validation errors also list the
+/// failed checks in `fields`, and the messages of database errors are not
+/// sent to the client.
+pub fn error(error: CRUDError) -> async_graphql::Error {
+    let (code, message) = match error.kind() {
+        CRUDErrorKind::NotFound => ("NOT_FOUND", error.to_string()),
+        CRUDErrorKind::Conflict => ("CONFLICT", error.to_string()),
+        CRUDErrorKind::Validation => ("VALIDATION", error.to_string()),
+        _ => ("DATABASE", "database error".to_string()),
+    };
+    let fields = error
+        .field_errors()
+        .iter()
+        .map(|error| {
+            serde_json::json!({
+                "field": error.field,
+                "rule": error.rule,
+                "message": error.message,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    async_graphql::Error::new(message).extend_with(|_, extensions| {
+        extensions.set("code", code);
+        if !fields.is_empty() {
+            extensions.set(
+                "fields",
+                Value::from_json(fields.into()).unwrap_or(Value::Null),
+            );
+        }
+    })
+}
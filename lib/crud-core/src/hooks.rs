@@ -0,0 +1,77 @@
+use std::future::Future;
+
+use sqlx::SqliteConnection;
+
+use crate::entity::Entity;
+use crate::error::CRUDError;
+
+/// Values of a row about to be inserted, implemented by `#[derive(Create)]`
+/// with `#[crud_hooks(before_create)]`.
+pub trait CreateValues {
+    /// Struct named `New<Struct>`, with a public field for every parameter
+    /// of the derived `create` function.
+    type Values;
+}
+
+/// Hook run by `create` before inserting the row, enabled with
+/// `#[crud_hooks(before_create)]`.
+///
+/// The hook may change the values, or abort the insertion by returning an
+/// error. It runs on the connection of the insertion, so its queries are part
+/// of the same transaction.
+pub trait BeforeCreate: CreateValues {
+    fn before_create(
+        connection: &mut SqliteConnection,
+        values: &mut Self::Values,
+    ) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
+
+/// Hook run by `create` with the inserted row, enabled with
+/// `#[crud_hooks(after_create)]`.
+pub trait AfterCreate {
+    fn after_create(
+        &self,
+        connection: &mut SqliteConnection,
+    ) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
+
+/// Hook run by `update` before writing the row, enabled with
+/// `#[crud_hooks(before_update)]`. Returning an error aborts the update.
+pub trait BeforeUpdate {
+    fn before_update(
+        &self,
+        connection: &mut SqliteConnection,
+    ) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
+
+/// Hook run by `update` with the written row, enabled with
+/// `#[crud_hooks(after_update)]`.
+pub trait AfterUpdate {
+    fn after_update(
+        &self,
+        connection: &mut SqliteConnection,
+    ) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
+
+/// Hook run by `delete` before deleting the row with the key `id`, enabled
+/// with `#[crud_hooks(before_delete)]`. Returning an error aborts the
+/// deletion.
+///
+/// The key type comes from [`Entity`], so the struct must also derive `Read`.
+pub trait BeforeDelete: Entity {
+    fn before_delete(
+        connection: &mut SqliteConnection,
+        id: &Self::Id,
+    ) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
+
+/// Hook run by `delete` once the row with the key `id` is deleted, enabled
+/// with `#[crud_hooks(after_delete)]`.
+///
+/// The key type comes from [`Entity`], so the struct must also derive `Read`.
+pub trait AfterDelete: Entity {
+    fn after_delete(
+        connection: &mut SqliteConnection,
+        id: &Self::Id,
+    ) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
@@ -0,0 +1,68 @@
+//! Conversions of `#[crud_json]` fields, stored as JSON in a TEXT column.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::CRUDError;
+
+/// Serializes the value of a field to the text bound to its column.
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<String, CRUDError> {
+    serde_json::to_string(value).map_err(|e| CRUDError::new(e.to_string()))
+}
+
+/// Serializes the value of an `Option` field, keeping `None` as `NULL`.
+pub fn encode_option<T: Serialize>(value: &Option<T>) -> Result<Option<String>, CRUDError> {
+    value.as_ref().map(encode).transpose()
+}
+
+/// Deserializes the text of a column into the value of its field.
+///
+/// Errors are reported as failures to decode the column, so they surface
+/// from the query like any other decoding error.
+pub fn decode<T: DeserializeOwned>(text: &str) -> Result<T, sqlx::Error> {
+    serde_json::from_str(text).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+/// The text of the column of a `#[crud_json]` field, as read by the checked
+/// queries.
+///
+/// The column is read into this type rather than `String`, so that the error
+/// for a column which does not hold text only lists the conversion below.
+pub struct Text(String);
+
+impl From<String> for Text {
+    fn from(text: String) -> Self {
+        Self(text)
+    }
+}
+
+impl Text {
+    /// Deserializes the text into the value of a field.
+    pub fn decode<T: DeserializeOwned>(self) -> Result<T, sqlx::Error> {
+        decode(&self.0)
+    }
+}
+
+/// The text of the column of an `Option` field marked `#[crud_json]`, see
+/// [`Text`].
+pub struct NullableText(Option<String>);
+
+impl From<String> for NullableText {
+    fn from(text: String) -> Self {
+        Self(Some(text))
+    }
+}
+
+impl From<Option<String>> for NullableText {
+    fn from(text: Option<String>) -> Self {
+        Self(text)
+    }
+}
+
+impl NullableText {
+    /// Deserializes the text into the value of a field, keeping `NULL` as
+    /// `None`.
+    pub fn decode<T: DeserializeOwned>(self) -> Result<Option<T>, sqlx::Error> {
+        self.0.as_deref().map(decode).transpose()
+    }
+}
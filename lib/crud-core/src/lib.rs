@@ -1 +1,18 @@
+pub mod audit;
+pub mod entity;
 pub mod error;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod hooks;
+pub mod json;
+pub mod migration;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod observer;
+pub mod outbox;
+pub mod registry;
+pub mod repository;
+pub mod schema;
+#[cfg(feature = "tracing")]
+pub mod trace;
+pub mod validation;
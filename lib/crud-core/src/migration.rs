@@ -0,0 +1,410 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sqlx::{Pool, Sqlite};
+
+use crate::error::CRUDError;
+use crate::schema::{ColumnSchema, TableSchema};
+
+/// Column of a live table, as reported by `pragma_table_info` and the
+/// constraints of the table.
+#[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
+pub struct TableColumn {
+    /// Name of the column.
+    pub name: String,
+    /// Declared type of the column.
+    #[sqlx(rename = "type")]
+    pub sql_type: String,
+    /// Whether the column has a `NOT NULL` constraint.
+    #[sqlx(rename = "notnull")]
+    pub not_null: bool,
+    /// SQL expression of the `DEFAULT` value.
+    #[sqlx(rename = "dflt_value")]
+    pub default: Option<String>,
+    /// Position of the column in the primary key, `0` if not part of it.
+    #[sqlx(rename = "pk")]
+    pub primary_key: i64,
+    /// Whether the column alone has a `UNIQUE` constraint.
+    #[sqlx(skip)]
+    pub unique: bool,
+    /// SQL expression of the `CHECK` constraint of the column definition.
+    #[sqlx(skip)]
+    pub check: Option<String>,
+    /// Referenced table and column, as reported by `pragma_foreign_key_list`.
+    #[sqlx(skip)]
+    pub references: Option<(String, String)>,
+}
+
+/// Statements bringing a database up to date with the derived structs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Migration {
+    /// SQL statements, in execution order.
+    pub statements: Vec<String>,
+}
+
+impl Migration {
+    /// Returns whether the database already matches the derived structs.
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Returns the statements as the content of a migration file.
+    pub fn to_sql(&self) -> String {
+        self.statements
+            .iter()
+            .map(|statement| format!("{statement};\n"))
+            .collect()
+    }
+
+    /// Writes the migration to `<directory>/<version>_<description>.sql`, the
+    /// layout read by `sqlx migrate run` and `sqlx::migrate!`.
+    ///
+    /// The version is the current UTC time formatted as `YYYYMMDDHHMMSS`, the
+    /// same format used by `sqlx migrate add`.
+    pub fn write(&self, directory: &Path, description: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(directory)?;
+
+        let description = description.trim().replace([' ', '-'], "_");
+        let path = directory.join(format!("{}_{description}.sql", migration_version()));
+        std::fs::write(&path, self.to_sql())?;
+
+        Ok(path)
+    }
+}
+
+/// Returns the columns of a table, or an empty list if the table does not exist.
+pub async fn table_columns(
+    pool: &Pool<Sqlite>,
+    table: &str,
+) -> Result<Vec<TableColumn>, CRUDError> {
+    let mut columns = sqlx::query_as::<_, TableColumn>(
+        "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?) ORDER BY cid",
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| CRUDError::new(e.to_string()))?;
+    if columns.is_empty() {
+        return Ok(columns);
+    }
+
+    // Single-column unique indexes, whether declared inline or as a table
+    // constraint, but not those created with `CREATE UNIQUE INDEX`.
+    let unique: Vec<String> = sqlx::query_scalar(
+        "SELECT info.name FROM pragma_index_list(?1) AS list, pragma_index_info(list.name) AS info \
+         WHERE list.\"unique\" AND list.origin = 'u' \
+         AND (SELECT COUNT(*) FROM pragma_index_info(list.name)) = 1",
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| CRUDError::new(e.to_string()))?;
+    let foreign_keys: Vec<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT \"from\", \"table\", \"to\" FROM pragma_foreign_key_list(?)")
+            .bind(table)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| CRUDError::new(e.to_string()))?;
+    let sql: Option<String> =
+        sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| CRUDError::new(e.to_string()))?
+            .flatten();
+    let checks = sql.as_deref().map(column_checks).unwrap_or_default();
+
+    for column in &mut columns {
+        column.unique = unique.contains(&column.name);
+        column.references = foreign_keys
+            .iter()
+            .find(|(from, _, _)| *from == column.name)
+            .map(|(_, table, to)| (table.clone(), to.clone().unwrap_or_default()));
+        column.check = checks
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&column.name))
+            .map(|(_, check)| check.clone());
+    }
+
+    Ok(columns)
+}
+
+/// Returns the `CHECK` expressions of the column definitions of a
+/// `CREATE TABLE` statement, keyed by column name.
+fn column_checks(sql: &str) -> Vec<(String, String)> {
+    let (Some(start), Some(end)) = (sql.find('('), sql.rfind(')')) else {
+        return Vec::new();
+    };
+
+    split_top_level(&sql[start + 1..end])
+        .into_iter()
+        .filter_map(|definition| {
+            let definition = definition.trim();
+            let name = definition.split_whitespace().next()?;
+            let name = name.trim_matches(|c| matches!(c, '"' | '`' | '[' | ']'));
+            let upper = definition.to_ascii_uppercase();
+            let keyword = upper
+                .match_indices("CHECK")
+                .map(|(index, _)| index)
+                .find(|&index| {
+                    upper[..index].ends_with(char::is_whitespace)
+                        && upper[index + 5..].trim_start().starts_with('(')
+                })?;
+            let open = keyword + definition[keyword..].find('(')?;
+            let body = &definition[open + 1..];
+            let close = split_top_level_at(body, ')')?;
+            Some((name.to_string(), body[..close].trim().to_string()))
+        })
+        .collect()
+}
+
+/// Splits a list of definitions at the commas outside of parentheses and
+/// quotes.
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = list;
+    while let Some(index) = split_top_level_at(rest, ',') {
+        parts.push(&rest[..index]);
+        rest = &rest[index + 1..];
+    }
+    parts.push(rest);
+
+    parts
+}
+
+/// Returns the index of the first `separator` outside of parentheses and quotes.
+fn split_top_level_at(text: &str, separator: char) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, c) if c == separator && depth == 0 => return Some(index),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Compares the given table definitions to the database and returns the
+/// statements needed to bring the database up to date.
+///
+/// Tables present in the database but not in `schemas` are left untouched.
+pub async fn diff(pool: &Pool<Sqlite>, schemas: &[&TableSchema]) -> Result<Migration, CRUDError> {
+    let mut migration = Migration::default();
+    for schema in schemas {
+        let existing = table_columns(pool, schema.name).await?;
+        migration.statements.extend(diff_table(schema, &existing)?);
+    }
+
+    Ok(migration)
+}
+
+/// Compares every registered table definition to the database.
+///
+/// See [`registered_schemas`](crate::schema::registered_schemas).
+pub async fn diff_registered(pool: &Pool<Sqlite>) -> Result<Migration, CRUDError> {
+    let mut schemas: Vec<_> = crate::schema::registered_schemas().collect();
+    schemas.dedup_by_key(|schema| schema.name);
+
+    diff(pool, &schemas).await
+}
+
+/// Returns the statements turning a table with the `existing` columns into the
+/// table described by `schema`.
+///
+/// Columns which SQLite can add in place are added with `ALTER TABLE ... ADD
+/// COLUMN`. Any other change rebuilds the table, copying the columns common to
+/// both definitions.
+///
+/// Fails if the rebuild would add a `NOT NULL` column without a `DEFAULT`,
+/// which the copied rows would have no value for.
+pub fn diff_table(
+    schema: &TableSchema,
+    existing: &[TableColumn],
+) -> Result<Vec<String>, CRUDError> {
+    if existing.is_empty() {
+        return Ok(vec![schema.create_table_sql()]);
+    }
+
+    let mut added = Vec::new();
+    let mut rebuild = existing
+        .iter()
+        .any(|column| schema.column(&column.name).is_none());
+    for column in schema.columns {
+        match existing.iter().find(|current| current.name == column.name) {
+            Some(current) => rebuild |= column_changed(schema, column, current),
+            None if can_add_column(schema, column) => added.push(column),
+            None => rebuild = true,
+        }
+    }
+
+    if rebuild {
+        return rebuild_table(schema, existing);
+    }
+
+    Ok(added
+        .into_iter()
+        .map(|column| {
+            format!(
+                "ALTER TABLE \"{}\" ADD COLUMN {}",
+                schema.name,
+                schema.column_definition(column)
+            )
+        })
+        .collect())
+}
+
+/// Returns whether the column of the live table differs from its definition.
+fn column_changed(schema: &TableSchema, column: &ColumnSchema, current: &TableColumn) -> bool {
+    let in_key = schema.primary_key.contains(&column.name);
+
+    // `INTEGER PRIMARY KEY` columns alias the rowid and are never null,
+    // whether or not they were declared `NOT NULL`.
+    let nullability_changed = !in_key && current.not_null == column.nullable;
+
+    let references_changed = match (&current.references, column.references) {
+        (None, None) => false,
+        (Some((table, to)), Some(references)) => {
+            !table.eq_ignore_ascii_case(references.table)
+                || !(to.is_empty() || to.eq_ignore_ascii_case(references.column))
+        }
+        _ => true,
+    };
+
+    !current.sql_type.eq_ignore_ascii_case(column.sql_type)
+        || nullability_changed
+        || (current.primary_key > 0) != in_key
+        || !same_expression(current.default.as_deref(), column.default)
+        || !same_expression(current.check.as_deref(), column.check)
+        || current.unique != column.unique
+        || references_changed
+}
+
+/// Returns whether two optional SQL expressions are written the same way.
+fn same_expression(current: Option<&str>, expected: Option<&str>) -> bool {
+    match (current, expected) {
+        (Some(current), Some(expected)) => current.trim().eq_ignore_ascii_case(expected.trim()),
+        (current, expected) => current.is_none() && expected.is_none(),
+    }
+}
+
+/// Returns whether SQLite accepts the column in an `ALTER TABLE ... ADD COLUMN`.
+fn can_add_column(schema: &TableSchema, column: &ColumnSchema) -> bool {
+    let constant_default = column
+        .default
+        .is_some_and(|default| !default.contains('(') && !default.contains("CURRENT_"));
+
+    !schema.primary_key.contains(&column.name)
+        && !column.unique
+        && (column.nullable || constant_default)
+        && (column.references.is_none() || column.nullable)
+        && (column.default.is_none() || constant_default)
+}
+
+/// Returns the statements recreating the table and copying its rows.
+///
+/// Columns which become `NOT NULL` are filled with their `DEFAULT` where the
+/// copied value is `NULL`.
+fn rebuild_table(schema: &TableSchema, existing: &[TableColumn]) -> Result<Vec<String>, CRUDError> {
+    let temporary = format!("{}__new", schema.name);
+
+    // A single INTEGER key aliases the rowid, which SQLite assigns itself.
+    let rowid_key = match schema.primary_key {
+        [key] => schema
+            .column(key)
+            .is_some_and(|column| column.sql_type == "INTEGER"),
+        _ => false,
+    };
+    let missing = schema.columns.iter().find(|column| {
+        let copied = existing.iter().any(|current| current.name == column.name);
+        let assigned = rowid_key && schema.primary_key.contains(&column.name);
+        !(column.nullable || column.default.is_some() || copied || assigned)
+    });
+    if let Some(column) = missing {
+        return Err(CRUDError::new(format!(
+            "cannot add the NOT NULL column `{}` to the existing rows of `{}` without a DEFAULT",
+            column.name, schema.name
+        )));
+    }
+
+    let common: Vec<_> = schema
+        .columns
+        .iter()
+        .filter_map(|column| {
+            let current = existing
+                .iter()
+                .find(|current| current.name == column.name)?;
+            Some((column, current))
+        })
+        .collect();
+    let columns = common
+        .iter()
+        .map(|(column, _)| column.name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = common
+        .iter()
+        .map(|(column, current)| match column.default {
+            Some(default) if !column.nullable && !current.not_null => {
+                format!("COALESCE({}, {default})", column.name)
+            }
+            _ => column.name.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(vec![
+        // `PRAGMA foreign_keys` cannot change inside the migration transaction,
+        // so foreign key checks are deferred until it commits instead.
+        "PRAGMA defer_foreign_keys = ON".to_string(),
+        format!(
+            "CREATE TABLE \"{temporary}\" ({})",
+            schema.definitions().join(", ")
+        ),
+        format!(
+            "INSERT INTO \"{temporary}\" ({columns}) SELECT {values} FROM \"{}\"",
+            schema.name
+        ),
+        format!("DROP TABLE \"{}\"", schema.name),
+        format!("ALTER TABLE \"{temporary}\" RENAME TO \"{}\"", schema.name),
+    ])
+}
+
+/// Returns the current UTC time formatted as `YYYYMMDDHHMMSS`.
+fn migration_version() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // Converts days since the epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}{month:02}{day:02}{:02}{:02}{:02}",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
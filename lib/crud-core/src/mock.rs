@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+
+use crate::entity::Entity;
+use crate::error::CRUDError;
+use crate::repository::{CreateRepository, DeleteRepository, ReadRepository, UpdateRepository};
+
+/// Preparation of a new row by a [`MockRepository`], implemented by
+/// `#[derive(Create)]` with the `mock` feature.
+pub trait MockCreate {
+    /// Sets the fields the database would set on insertion: the key, when it
+    /// is generated, from `sequence`, and the `#[crud_version]` to `1`.
+    fn prepare_create(&mut self, sequence: i64) -> Result<(), CRUDError>;
+}
+
+/// Preparation of an updated row by a [`MockRepository`], implemented by
+/// `#[derive(Update)]` with the `mock` feature.
+pub trait MockUpdate {
+    /// Checks the `#[crud_version]` against the `stored` row and sets the
+    /// fields the database would set on update.
+    fn prepare_update(&mut self, stored: &Self) -> Result<(), CRUDError>;
+
+    /// Returns the error of updating a row which does not exist.
+    fn missing_row() -> CRUDError;
+}
+
+/// In-memory table of `T`, keyed by its `#[crud_id]`.
+///
+/// The repository implements the same repository traits as `Pool<Sqlite>`,
+/// so code written against them can be tested without a database. Keys
+/// generated by the database are assigned from an auto-incrementing sequence
+/// starting at `1`.
+///
+/// Timestamps are left as given, and relationships are not supported. Neither
+/// the `#[crud_validate]` checks nor the hooks are run, so the repository
+/// accepts rows which the database one rejects.
+pub struct MockRepository<T: Entity> {
+    table: Mutex<MockTable<T>>,
+}
+
+struct MockTable<T: Entity> {
+    rows: HashMap<T::Id, (u64, T)>,
+    next_row: u64,
+    next_id: i64,
+}
+
+impl<T: Entity> MockRepository<T>
+where
+    T::Id: Hash + Eq,
+{
+    /// Creates an empty repository.
+    pub fn new() -> MockRepository<T> {
+        MockRepository {
+            table: Mutex::new(MockTable {
+                rows: HashMap::new(),
+                next_row: 0,
+                next_id: 1,
+            }),
+        }
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.table.lock().unwrap().rows.len()
+    }
+
+    /// Returns whether the repository has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Entity> Default for MockRepository<T>
+where
+    T::Id: Hash + Eq,
+{
+    fn default() -> Self {
+        MockRepository::new()
+    }
+}
+
+impl<T> CreateRepository<T> for MockRepository<T>
+where
+    T: Entity + MockCreate + Clone + Send,
+    T::Id: Hash + Eq + Send,
+{
+    async fn create(&self, mut item: T) -> Result<T, CRUDError> {
+        let mut table = self.table.lock().unwrap();
+
+        item.prepare_create(table.next_id)?;
+        let id = item.id();
+        if table.rows.contains_key(&id) {
+            return Err(CRUDError::new("UNIQUE constraint failed: duplicate key"));
+        }
+
+        table.next_id += 1;
+        let row = table.next_row;
+        table.next_row += 1;
+        table.rows.insert(id, (row, item.clone()));
+
+        Ok(item)
+    }
+}
+
+impl<T> ReadRepository<T> for MockRepository<T>
+where
+    T: Entity + Clone + Send,
+    T::Id: Hash + Eq + Send,
+{
+    async fn read_one(&self, id: T::Id) -> Result<T, CRUDError> {
+        let table = self.table.lock().unwrap();
+        match table.rows.get(&id) {
+            Some((_, item)) => Ok(item.clone()),
+            None => Err(CRUDError::not_found("no row matched the key")),
+        }
+    }
+
+    async fn read_all(&self) -> Result<Vec<T>, CRUDError> {
+        let table = self.table.lock().unwrap();
+        let mut rows: Vec<_> = table.rows.values().collect();
+        rows.sort_by_key(|(row, _)| *row);
+
+        Ok(rows.into_iter().map(|(_, item)| item.clone()).collect())
+    }
+}
+
+impl<T> UpdateRepository<T> for MockRepository<T>
+where
+    T: Entity + MockUpdate + Clone + Send,
+    T::Id: Hash + Eq + Send,
+{
+    async fn update(&self, item: &mut T) -> Result<(), CRUDError> {
+        let mut table = self.table.lock().unwrap();
+        let Some((_, stored)) = table.rows.get_mut(&item.id()) else {
+            return Err(T::missing_row());
+        };
+
+        item.prepare_update(stored)?;
+        *stored = item.clone();
+
+        Ok(())
+    }
+}
+
+impl<T> DeleteRepository<T> for MockRepository<T>
+where
+    T: Entity + Send + Sync,
+    T::Id: Hash + Eq + Send,
+{
+    async fn delete(&self, item: &T) -> Result<(), CRUDError> {
+        match self.table.lock().unwrap().rows.remove(&item.id()) {
+            Some(_) => Ok(()),
+            None => Err(CRUDError::not_found("no row matched the key")),
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::cell::Cell;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::entity::Entity;
+use crate::error::{CRUDError, CRUDErrorKind};
+use crate::repository::{CreateRepository, DeleteRepository, ReadRepository, UpdateRepository};
+
+/// Completed call of a generated query function, or of a repository method
+/// through an [`ObservedRepository`].
+#[derive(Debug, Clone)]
+pub struct QueryEvent<'a> {
+    /// Name of the struct, such as `User`.
+    pub entity: &'a str,
+    /// Name of the database table.
+    pub table: &'a str,
+    /// Name of the function, such as `read_one` or `set_tags`.
+    pub operation: &'a str,
+    /// Time spent from the call until the result, or until the end of the
+    /// stream returned by `read`.
+    pub duration: Duration,
+    /// Number of rows read or written, when it can be told.
+    pub rows: Option<u64>,
+    /// Kind of the error returned by the call, if it failed.
+    pub outcome: Result<(), CRUDErrorKind>,
+}
+
+/// Receiver of a [`QueryEvent`] for every completed operation, to collect
+/// metrics or logs.
+///
+/// An observer is either set once for the whole program with
+/// [`set_observer`], where it sees every generated query function, or
+/// attached to a single pool with [`ObservedRepository`], where it sees the
+/// calls made through the repository traits instead of the global observer.
+pub trait CrudObserver: Send + Sync {
+    /// Handles a completed operation. This runs on the calling task, so it
+    /// should not block.
+    fn observe(&self, event: &QueryEvent<'_>);
+}
+
+static OBSERVER: OnceLock<Box<dyn CrudObserver>> = OnceLock::new();
+
+thread_local! {
+    /// Set while an [`ObservedRepository`] polls a call it reports itself.
+    static REPORTED_BY_REPOSITORY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Error returned by [`set_observer`] when an observer is already set.
+#[derive(Debug)]
+pub struct ObserverAlreadySet;
+
+impl std::fmt::Display for ObserverAlreadySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a global CRUD observer is already set")
+    }
+}
+
+impl std::error::Error for ObserverAlreadySet {}
+
+/// Sets the observer of every generated query function. It can only be set
+/// once, and stays set for the rest of the program.
+pub fn set_observer(observer: impl CrudObserver + 'static) -> Result<(), ObserverAlreadySet> {
+    OBSERVER
+        .set(Box::new(observer))
+        .map_err(|_| ObserverAlreadySet)
+}
+
+/// Reports an event to the global observer, if one is set and the event is
+/// not part of a call through an [`ObservedRepository`]. Called by the
+/// generated query functions.
+pub fn observe(event: &QueryEvent<'_>) {
+    if REPORTED_BY_REPOSITORY.get() {
+        return;
+    }
+    if let Some(observer) = OBSERVER.get() {
+        observer.observe(event);
+    }
+}
+
+/// Reports the result of a generated query function, started at `started`,
+/// to the global observer.
+pub fn report<T>(
+    entity: &str,
+    table: &str,
+    operation: &str,
+    started: Instant,
+    result: &Result<T, CRUDError>,
+    rows: Option<u64>,
+) {
+    observe(&QueryEvent {
+        entity,
+        table,
+        operation,
+        duration: started.elapsed(),
+        rows,
+        outcome: result.as_ref().map(|_| ()).map_err(CRUDError::kind),
+    });
+}
+
+/// Repository reporting the calls made through the repository traits to its
+/// own observer, before forwarding them to the wrapped repository.
+///
+/// ```rust,ignore
+/// let users = ObservedRepository::new(pool.clone(), MetricsObserver);
+/// let debbie = users.read_one(1).await?;
+/// ```
+///
+/// A call through the repository is only reported to its observer, and not
+/// to the global one, while calls made through the generated functions are
+/// only reported to the global observer.
+pub struct ObservedRepository<R> {
+    repository: R,
+    observer: Arc<dyn CrudObserver>,
+}
+
+impl<R> ObservedRepository<R> {
+    /// Wraps `repository`, reporting its calls to `observer`.
+    pub fn new(repository: R, observer: impl CrudObserver + 'static) -> ObservedRepository<R> {
+        ObservedRepository {
+            repository,
+            observer: Arc::new(observer),
+        }
+    }
+
+    /// Returns the wrapped repository.
+    pub fn inner(&self) -> &R {
+        &self.repository
+    }
+
+    /// Runs `call` on the wrapped repository without reporting it to the
+    /// global observer.
+    async fn call<F: Future>(call: F) -> F::Output {
+        let mut call = pin!(call);
+        poll_fn(|cx| {
+            let reported = REPORTED_BY_REPOSITORY.replace(true);
+            let poll = call.as_mut().poll(cx);
+            REPORTED_BY_REPOSITORY.set(reported);
+            poll
+        })
+        .await
+    }
+
+    fn report<T: Entity, U>(
+        &self,
+        operation: &str,
+        started: Instant,
+        result: &Result<U, CRUDError>,
+        rows: impl FnOnce(&U) -> Option<u64>,
+    ) {
+        self.observer.observe(&QueryEvent {
+            entity: T::META.name,
+            table: T::TABLE,
+            operation,
+            duration: started.elapsed(),
+            rows: result.as_ref().ok().and_then(rows),
+            outcome: result.as_ref().map(|_| ()).map_err(CRUDError::kind),
+        });
+    }
+}
+
+impl<T, R> CreateRepository<T> for ObservedRepository<R>
+where
+    T: Entity + Send,
+    R: CreateRepository<T> + Sync,
+{
+    async fn create(&self, item: T) -> Result<T, CRUDError> {
+        let started = Instant::now();
+        let result = Self::call(self.repository.create(item)).await;
+        self.report::<T, _>("create", started, &result, |_| Some(1));
+        result
+    }
+}
+
+impl<T, R> ReadRepository<T> for ObservedRepository<R>
+where
+    T: Entity,
+    T::Id: Send,
+    R: ReadRepository<T> + Sync,
+{
+    async fn read_one(&self, id: T::Id) -> Result<T, CRUDError> {
+        let started = Instant::now();
+        let result = Self::call(self.repository.read_one(id)).await;
+        self.report::<T, _>("read_one", started, &result, |_| Some(1));
+        result
+    }
+
+    async fn read_all(&self) -> Result<Vec<T>, CRUDError> {
+        let started = Instant::now();
+        let result = Self::call(self.repository.read_all()).await;
+        self.report::<T, _>("read_all", started, &result, |items| {
+            Some(items.len() as u64)
+        });
+        result
+    }
+}
+
+impl<T, R> UpdateRepository<T> for ObservedRepository<R>
+where
+    T: Entity + Send,
+    R: UpdateRepository<T> + Sync,
+{
+    async fn update(&self, item: &mut T) -> Result<(), CRUDError> {
+        let started = Instant::now();
+        let result = Self::call(self.repository.update(item)).await;
+        self.report::<T, _>("update", started, &result, |_| Some(1));
+        result
+    }
+}
+
+impl<T, R> DeleteRepository<T> for ObservedRepository<R>
+where
+    T: Entity + Sync,
+    R: DeleteRepository<T> + Sync,
+{
+    async fn delete(&self, item: &T) -> Result<(), CRUDError> {
+        let started = Instant::now();
+        let result = Self::call(self.repository.delete(item)).await;
+        self.report::<T, _>("delete", started, &result, |_| None);
+        result
+    }
+}
+
+/// Observer recording every operation with the `metrics` crate, in the
+/// recorder installed by the program.
+///
+/// It records:
+///
+/// - `crud_operations_total`, a counter labelled with `entity`, `table`,
+///   `operation` and `outcome` (`ok`, or the error kind in snake case).
+/// - `crud_operation_duration_seconds`, a histogram labelled with `entity`,
+///   `table` and `operation`.
+/// - `crud_rows_total`, a counter of the rows read or written, labelled with
+///   `entity`, `table` and `operation`.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsObserver;
+
+#[cfg(feature = "metrics")]
+impl CrudObserver for MetricsObserver {
+    fn observe(&self, event: &QueryEvent<'_>) {
+        let outcome = match event.outcome {
+            Ok(()) => "ok",
+            Err(CRUDErrorKind::NotFound) => "not_found",
+            Err(CRUDErrorKind::Conflict) => "conflict",
+            Err(CRUDErrorKind::Database) => "database",
+            Err(CRUDErrorKind::Validation) => "validation",
+        };
+        let labels = [
+            ("entity", event.entity.to_string()),
+            ("table", event.table.to_string()),
+            ("operation", event.operation.to_string()),
+        ];
+
+        metrics::counter!(
+            "crud_operations_total",
+            &[
+                labels[0].clone(),
+                labels[1].clone(),
+                labels[2].clone(),
+                ("outcome", outcome.to_string()),
+            ]
+        )
+        .increment(1);
+        metrics::histogram!("crud_operation_duration_seconds", &labels)
+            .record(event.duration.as_secs_f64());
+        if let Some(rows) = event.rows {
+            metrics::counter!("crud_rows_total", &labels).increment(rows);
+        }
+    }
+}
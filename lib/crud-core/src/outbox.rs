@@ -0,0 +1,178 @@
+#[cfg(feature = "outbox-consumer")]
+use std::time::Duration;
+
+#[cfg(feature = "outbox-consumer")]
+use async_stream::try_stream;
+#[cfg(feature = "outbox-consumer")]
+use futures_core::Stream;
+use sqlx::SqliteConnection;
+#[cfg(feature = "outbox-consumer")]
+use sqlx::SqlitePool;
+
+use crate::audit::entity_id;
+use crate::error::CRUDError;
+
+/// Change published to the outbox table by [`publish`].
+pub struct OutboxEntry<'a> {
+    /// Table of the changed row.
+    pub table: &'a str,
+    /// Columns of the key of the row.
+    pub id_columns: &'a [&'a str],
+    /// `create`, `update` or `delete`.
+    pub operation: &'a str,
+    /// Row as a JSON object after the change, or before it for deletions, or
+    /// `None` if there is no such row.
+    pub payload: Option<String>,
+}
+
+/// Inserts an event for `entry` in `outbox_table`, on the connection of the
+/// change. Called by the functions generated with `#[crud_outbox]`.
+///
+/// Nothing is published without a payload, such as when deleting a row which
+/// does not exist.
+pub async fn publish(
+    connection: &mut SqliteConnection,
+    outbox_table: &str,
+    entry: OutboxEntry<'_>,
+) -> Result<(), CRUDError> {
+    let Some(payload) = entry.payload else {
+        return Ok(());
+    };
+    let row: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&payload).map_err(|e| CRUDError::new(e.to_string()))?;
+
+    sqlx::query(&format!(
+        "INSERT INTO {outbox_table} (table_name, entity_id, operation, payload, created_at) \
+         VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)"
+    ))
+    .bind(entry.table)
+    .bind(entity_id(&row, entry.id_columns))
+    .bind(entry.operation)
+    .bind(&payload)
+    .execute(connection)
+    .await
+    .map_err(|e| CRUDError::new(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Event read from the outbox table.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct OutboxEvent {
+    /// Position of the event, increasing with every change.
+    pub id: i64,
+    /// Table of the changed row.
+    #[sqlx(rename = "table_name")]
+    pub table: String,
+    /// Key of the row, as text, or as a JSON array for composite keys.
+    pub entity_id: String,
+    /// `create`, `update` or `delete`.
+    pub operation: String,
+    /// Row as a JSON object after the change, or before it for deletions.
+    pub payload: String,
+    pub created_at: String,
+}
+
+/// Reader of the events of an outbox table, in the order of the changes,
+/// available with the `outbox-consumer` feature.
+///
+/// Events stay in the table until they are acknowledged, so an event which
+/// was read but not acknowledged is read again by the next consumer: delivery
+/// is at least once. SQLite commits one write transaction at a time, so
+/// events are never committed behind an event already read.
+///
+/// ```rust,ignore
+/// let consumer = OutboxConsumer::new(pool.clone(), "outbox");
+/// let mut events = pin!(consumer.stream());
+/// while let Some(event) = events.try_next().await? {
+///     search_index.apply(&event).await?;
+///     consumer.acknowledge(&event).await?;
+/// }
+/// ```
+#[cfg(feature = "outbox-consumer")]
+pub struct OutboxConsumer {
+    pool: SqlitePool,
+    outbox_table: String,
+    batch_size: u32,
+    poll_interval: Duration,
+}
+
+#[cfg(feature = "outbox-consumer")]
+impl OutboxConsumer {
+    /// Creates a consumer of `outbox_table`, reading up to 100 events at once
+    /// and polling every second once every event is read.
+    pub fn new(pool: SqlitePool, outbox_table: impl Into<String>) -> OutboxConsumer {
+        OutboxConsumer {
+            pool,
+            outbox_table: outbox_table.into(),
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the maximum number of events read by a query.
+    pub fn batch_size(mut self, batch_size: u32) -> OutboxConsumer {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Sets the time the stream waits before reading new events once every
+    /// event is read.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> OutboxConsumer {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Reads the oldest events which are not acknowledged, after the event
+    /// with the position `after`, if any.
+    pub async fn fetch(&self, after: Option<i64>) -> Result<Vec<OutboxEvent>, CRUDError> {
+        sqlx::query_as(&format!(
+            "SELECT id, table_name, entity_id, operation, payload, created_at FROM {} \
+             WHERE acknowledged_at IS NULL AND id > ? ORDER BY id LIMIT ?",
+            self.outbox_table
+        ))
+        .bind(after.unwrap_or(i64::MIN))
+        .bind(self.batch_size)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CRUDError::new(e.to_string()))
+    }
+
+    /// Marks the event as processed, so that no consumer reads it again.
+    pub async fn acknowledge(&self, event: &OutboxEvent) -> Result<(), CRUDError> {
+        sqlx::query(&format!(
+            "UPDATE {} SET acknowledged_at = CURRENT_TIMESTAMP WHERE id = ?",
+            self.outbox_table
+        ))
+        .bind(event.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CRUDError::new(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Streams every event which is not acknowledged, in order, then waits
+    /// for new ones. The stream never ends, unless reading fails.
+    ///
+    /// The stream moves past every event it yields, so an event which is not
+    /// acknowledged is skipped by the rest of the stream, and only read again
+    /// by a new stream or consumer.
+    pub fn stream(&self) -> impl Stream<Item = Result<OutboxEvent, CRUDError>> + '_ {
+        try_stream! {
+            let mut after = None;
+            loop {
+                let events = self.fetch(after).await?;
+                if events.is_empty() {
+                    tokio::time::sleep(self.poll_interval).await;
+                    continue;
+                }
+
+                for event in events {
+                    after = Some(event.id);
+                    yield event;
+                }
+            }
+        }
+    }
+}
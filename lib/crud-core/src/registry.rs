@@ -0,0 +1,125 @@
+use crate::schema::TableSchema;
+
+/// Column of a derived struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnMeta {
+    /// Name of the column, which is also the name of the field.
+    pub name: &'static str,
+    /// Rust type of the field, as written in the struct.
+    pub rust_type: &'static str,
+    /// Whether the field is an `Option`.
+    pub nullable: bool,
+    /// Whether the field is annotated with `#[crud_id]`.
+    pub primary_key: bool,
+}
+
+/// Attributes enabled on a derived struct.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntityFlags {
+    /// The key is supplied by the caller or generated in Rust rather than by the database.
+    pub client_generated_id: bool,
+    /// Name of the field annotated with `#[crud_created_at]`.
+    pub created_at: Option<&'static str>,
+    /// Name of the field annotated with `#[crud_updated_at]`.
+    pub updated_at: Option<&'static str>,
+    /// Name of the field annotated with `#[crud_version]`.
+    pub version: Option<&'static str>,
+}
+
+/// Derives of `derive_crud` implemented by a struct.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntityDerives {
+    /// Whether the struct derives `Create`.
+    pub create: bool,
+    /// Whether the struct derives `Read`.
+    pub read: bool,
+    /// Whether the struct derives `Update`.
+    pub update: bool,
+    /// Whether the struct derives `Delete`.
+    pub delete: bool,
+    /// Whether the struct derives `Schema`.
+    pub schema: bool,
+}
+
+impl EntityDerives {
+    /// Returns the derives implemented by either set.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            create: self.create || other.create,
+            read: self.read || other.read,
+            update: self.update || other.update,
+            delete: self.delete || other.delete,
+            schema: self.schema || other.schema,
+        }
+    }
+}
+
+/// Description of a derived struct, available at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntityMeta {
+    /// Name of the struct.
+    pub name: &'static str,
+    /// Module the struct is declared in, as given by `module_path!`.
+    pub module: &'static str,
+    /// Name of the database table given in `#[crud_table]`.
+    pub table: &'static str,
+    /// Columns in field declaration order.
+    pub columns: &'static [ColumnMeta],
+    /// Names of the `#[crud_id]` columns, in field declaration order.
+    pub id_columns: &'static [&'static str],
+    /// Rust type of the key, a tuple for composite keys.
+    pub id_type: &'static str,
+    /// Attributes enabled on the struct.
+    pub flags: EntityFlags,
+    /// Derives implemented by the struct.
+    pub derives: EntityDerives,
+    /// Table definition of a struct deriving `Schema`.
+    pub schema: Option<&'static TableSchema>,
+}
+
+impl EntityMeta {
+    /// Returns the column with the given name.
+    pub fn column(&self, name: &str) -> Option<&ColumnMeta> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+/// Description submitted to the entity registry by each derive of a struct,
+/// with only that derive set in [`EntityMeta::derives`].
+#[derive(Debug)]
+pub struct EntityRegistration(pub &'static EntityMeta);
+
+inventory::collect!(EntityRegistration);
+
+/// Returns the description of every non-generic derived struct linked into the
+/// binary, ordered by table name.
+///
+/// The registrations of the derives of a struct are merged into a single
+/// description listing all of them.
+pub fn registered_entities() -> Vec<EntityMeta> {
+    let mut entities: Vec<EntityMeta> = Vec::new();
+    for registration in inventory::iter::<EntityRegistration> {
+        let meta = registration.0;
+        let entity = entities
+            .iter_mut()
+            .find(|entity| entity.module == meta.module && entity.name == meta.name);
+        match entity {
+            Some(entity) => {
+                entity.derives = entity.derives.union(meta.derives);
+                entity.schema = entity.schema.or(meta.schema);
+            }
+            None => entities.push(*meta),
+        }
+    }
+    entities.sort_by_key(|entity| (entity.table, entity.name, entity.module));
+
+    entities
+}
+
+/// Returns the registered entities stored in the given table.
+pub fn registered_entities_for(table: &str) -> Vec<EntityMeta> {
+    registered_entities()
+        .into_iter()
+        .filter(|entity| entity.table == table)
+        .collect()
+}
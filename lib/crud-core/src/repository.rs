@@ -0,0 +1,84 @@
+use std::future::Future;
+
+use crate::entity::{Entity, Filterable};
+use crate::error::CRUDError;
+
+/// Storage able to insert rows of `T`, implemented for `Pool<Sqlite>` by
+/// `#[derive(Create)]`.
+///
+/// Code written against these traits instead of the generated functions can
+/// run on a `MockRepository` in tests, see the `mock` feature.
+pub trait CreateRepository<T> {
+    /// Inserts the row, ignoring the fields managed by the database, and
+    /// returns it as stored.
+    fn create(&self, item: T) -> impl Future<Output = Result<T, CRUDError>> + Send;
+}
+
+/// Storage able to read rows of `T`, implemented for `Pool<Sqlite>` by
+/// `#[derive(Read)]`.
+pub trait ReadRepository<T: Entity> {
+    /// Reads the row with the given key.
+    fn read_one(&self, id: T::Id) -> impl Future<Output = Result<T, CRUDError>> + Send;
+
+    /// Reads every row.
+    fn read_all(&self) -> impl Future<Output = Result<Vec<T>, CRUDError>> + Send;
+}
+
+/// Range of rows read by [`PageRepository::read_page`], in key order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    /// Maximum number of rows.
+    pub limit: u32,
+    /// Number of matching rows skipped before the first one.
+    pub offset: u32,
+}
+
+impl Default for Page {
+    /// Returns the first 50 rows.
+    fn default() -> Self {
+        Page {
+            limit: 50,
+            offset: 0,
+        }
+    }
+}
+
+/// Storage able to list the rows of `T` matching a filter, one page at a
+/// time, implemented for `Pool<Sqlite>` by `#[derive(Read)]`.
+pub trait PageRepository<T: Filterable> {
+    /// Reads the rows matching `filter` within `page`, in key order.
+    fn read_page(
+        &self,
+        filter: &T::Filter,
+        page: Page,
+    ) -> impl Future<Output = Result<Vec<T>, CRUDError>> + Send;
+}
+
+/// Storage able to update rows of `T`, implemented for `Pool<Sqlite>` by
+/// `#[derive(Update)]`.
+pub trait UpdateRepository<T> {
+    /// Writes the row, refreshing the fields managed by the database. Fails
+    /// with `CRUDErrorKind::NotFound` when no row has the key of `item`.
+    fn update(&self, item: &mut T) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
+
+/// Storage able to delete rows of `T`, implemented for `Pool<Sqlite>` by
+/// `#[derive(Delete)]`.
+pub trait DeleteRepository<T> {
+    /// Deletes the row with the key of `item`, failing with
+    /// `CRUDErrorKind::NotFound` when there is none.
+    fn delete(&self, item: &T) -> impl Future<Output = Result<(), CRUDError>> + Send;
+}
+
+/// Storage offering every CRUD operation on `T`.
+pub trait Repository<T: Entity>:
+    CreateRepository<T> + ReadRepository<T> + UpdateRepository<T> + DeleteRepository<T>
+{
+}
+
+impl<T, R> Repository<T> for R
+where
+    T: Entity,
+    R: CreateRepository<T> + ReadRepository<T> + UpdateRepository<T> + DeleteRepository<T>,
+{
+}
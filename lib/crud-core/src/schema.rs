@@ -0,0 +1,129 @@
+/// Foreign key constraint of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForeignKey {
+    /// Referenced table.
+    pub table: &'static str,
+    /// Referenced column.
+    pub column: &'static str,
+}
+
+/// Definition of a single column, derived from a struct field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnSchema {
+    /// Name of the column.
+    pub name: &'static str,
+    /// SQLite type of the column, inferred from the field type or given with
+    /// `#[crud_column(sql_type = "...")]`.
+    pub sql_type: &'static str,
+    /// Whether the field is an `Option`.
+    pub nullable: bool,
+    /// Whether the column has a `UNIQUE` constraint.
+    pub unique: bool,
+    /// SQL expression used as the `DEFAULT` value.
+    pub default: Option<&'static str>,
+    /// SQL expression of a `CHECK` constraint.
+    pub check: Option<&'static str>,
+    /// Column referenced by a `REFERENCES` constraint.
+    pub references: Option<ForeignKey>,
+}
+
+/// Definition of a table, derived from a struct with `#[derive(Schema)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableSchema {
+    /// Name of the table given in `#[crud_table]`.
+    pub name: &'static str,
+    /// Columns in field declaration order.
+    pub columns: &'static [ColumnSchema],
+    /// Columns annotated with `#[crud_id]`.
+    pub primary_key: &'static [&'static str],
+}
+
+impl TableSchema {
+    /// Returns the column with the given name.
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Returns the definition of a column as written in a `CREATE TABLE` statement.
+    pub fn column_definition(&self, column: &ColumnSchema) -> String {
+        let mut definition = format!("{} {}", column.name, column.sql_type);
+
+        // A single INTEGER key is kept inline so it aliases the rowid.
+        if let [key] = self.primary_key {
+            if *key == column.name && column.sql_type == "INTEGER" {
+                definition.push_str(" PRIMARY KEY");
+            }
+        }
+        if !column.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if column.unique {
+            definition.push_str(" UNIQUE");
+        }
+        if let Some(default) = column.default {
+            definition.push_str(&format!(" DEFAULT {default}"));
+        }
+        if let Some(check) = column.check {
+            definition.push_str(&format!(" CHECK ({check})"));
+        }
+        if let Some(references) = column.references {
+            definition.push_str(&format!(
+                " REFERENCES {}({})",
+                references.table, references.column
+            ));
+        }
+
+        definition
+    }
+
+    /// Returns the column definitions and table constraints of the table.
+    pub(crate) fn definitions(&self) -> Vec<String> {
+        let mut definitions: Vec<_> = self
+            .columns
+            .iter()
+            .map(|column| self.column_definition(column))
+            .collect();
+
+        let inline_key = match self.primary_key {
+            [key] => self
+                .column(key)
+                .is_some_and(|column| column.sql_type == "INTEGER"),
+            _ => false,
+        };
+        if !inline_key && !self.primary_key.is_empty() {
+            definitions.push(format!("PRIMARY KEY ({})", self.primary_key.join(", ")));
+        }
+
+        definitions
+    }
+
+    /// Returns the `CREATE TABLE IF NOT EXISTS` statement of the table.
+    pub fn create_table_sql(&self) -> String {
+        format!(
+            "CREATE TABLE IF NOT EXISTS \"{}\" ({})",
+            self.name,
+            self.definitions().join(", ")
+        )
+    }
+
+    /// Returns the `DROP TABLE IF EXISTS` statement of the table.
+    pub fn drop_table_sql(&self) -> String {
+        format!("DROP TABLE IF EXISTS \"{}\"", self.name)
+    }
+}
+
+/// Table definition of a struct deriving `Schema`.
+pub trait Schema {
+    /// Definition of the table the struct is stored in.
+    const SCHEMA: TableSchema;
+}
+
+/// Returns the table definitions of every non-generic struct deriving `Schema`
+/// linked into the binary, ordered by table name.
+///
+/// See [`registered_entities`](crate::registry::registered_entities).
+pub fn registered_schemas() -> impl Iterator<Item = &'static TableSchema> {
+    crate::registry::registered_entities()
+        .into_iter()
+        .filter_map(|entity| entity.schema)
+}
@@ -0,0 +1,20 @@
+use tracing::field;
+use tracing::Span;
+
+use crate::error::CRUDError;
+
+/// Records the result of a generated function in its span: the number of
+/// rows read or written on success, when it can be told, or the kind of the
+/// error on failure.
+pub fn record<T>(span: &Span, result: &Result<T, CRUDError>, rows: Option<u64>) {
+    match result {
+        Ok(_) => {
+            if let Some(rows) = rows {
+                span.record("rows_affected", rows);
+            }
+        }
+        Err(error) => {
+            span.record("error", field::debug(error.kind()));
+        }
+    }
+}
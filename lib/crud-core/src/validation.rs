@@ -0,0 +1,153 @@
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::error::FieldError;
+
+/// Values with a length checked by `#[crud_validate(length(...))]`: the
+/// number of characters of text, or of elements of a collection.
+pub trait Length {
+    fn length(&self) -> usize;
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Regular expression of a `#[crud_validate(regex = "...")]` check, compiled
+/// on first use.
+pub struct Pattern {
+    source: &'static str,
+    regex: OnceLock<Regex>,
+}
+
+impl Pattern {
+    /// Creates the pattern from an expression already checked by the derive.
+    pub const fn new(source: &'static str) -> Pattern {
+        Pattern {
+            source,
+            regex: OnceLock::new(),
+        }
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        self.regex
+            .get_or_init(|| Regex::new(self.source).expect("pattern checked by the derive"))
+            .is_match(value)
+    }
+}
+
+// The checks below are called by the derived functions, and push a
+// `FieldError` for the failing field to `errors`.
+
+pub fn length<T: Length + ?Sized>(
+    errors: &mut Vec<FieldError>,
+    field: &'static str,
+    value: &T,
+    min: Option<usize>,
+    max: Option<usize>,
+) {
+    let length = value.length();
+    let message = match (min, max) {
+        (Some(min), Some(max)) if length < min || length > max => {
+            format!("length must be between {min} and {max}")
+        }
+        (Some(min), _) if length < min => format!("length must be at least {min}"),
+        (_, Some(max)) if length > max => format!("length must be at most {max}"),
+        _ => return,
+    };
+    errors.push(FieldError {
+        field,
+        rule: "length",
+        message,
+    });
+}
+
+pub fn range<T: PartialOrd + Display>(
+    errors: &mut Vec<FieldError>,
+    field: &'static str,
+    value: &T,
+    min: Option<T>,
+    max: Option<T>,
+) {
+    let message = match (min, max) {
+        (Some(min), Some(max)) if *value < min || *value > max => {
+            format!("must be between {min} and {max}")
+        }
+        (Some(min), _) if *value < min => format!("must be at least {min}"),
+        (_, Some(max)) if *value > max => format!("must be at most {max}"),
+        _ => return,
+    };
+    errors.push(FieldError {
+        field,
+        rule: "range",
+        message,
+    });
+}
+
+/// Checks for a single `@` between a non-empty local part and a domain with a
+/// dot, without whitespace.
+pub fn email(errors: &mut Vec<FieldError>, field: &'static str, value: &str) {
+    let valid = match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain
+                    .split_once('.')
+                    .is_some_and(|(name, suffix)| !name.is_empty() && !suffix.is_empty())
+                && !domain.ends_with('.')
+                && !value.contains(char::is_whitespace)
+        }
+        None => false,
+    };
+    if !valid {
+        errors.push(FieldError {
+            field,
+            rule: "email",
+            message: "must be an email address".to_string(),
+        });
+    }
+}
+
+pub fn regex(errors: &mut Vec<FieldError>, field: &'static str, value: &str, pattern: &Pattern) {
+    if !pattern.is_match(value) {
+        errors.push(FieldError {
+            field,
+            rule: "regex",
+            message: format!("must match `{}`", pattern.source),
+        });
+    }
+}
+
+/// Records the message returned by a `#[crud_validate(custom = function)]`
+/// check, if it failed.
+pub fn custom(errors: &mut Vec<FieldError>, field: &'static str, result: Result<(), String>) {
+    if let Err(message) = result {
+        errors.push(FieldError {
+            field,
+            rule: "custom",
+            message,
+        });
+    }
+}
@@ -1,3 +1,471 @@
+/// How the value of a `#[crud_id]` column is produced when a row is created.
+pub enum IdGeneration {
+    /// SQLite assigns the key, so it is left out of the `INSERT`.
+    Database,
+    /// The caller passes the key to `create`.
+    Client,
+    /// The key is produced by evaluating the given expression inside `create`.
+    Generator(syn::Expr),
+}
+
+/// Reads the options of a `#[crud_id(generated = "...", generator = "...")]` attribute.
+pub fn parse_id_generation_options(
+    attr: &syn::Attribute,
+    is_composite: bool,
+) -> syn::Result<IdGeneration> {
+    let mut generated: Option<syn::LitStr> = None;
+    let mut generator: Option<syn::Expr> = None;
+
+    if let syn::Meta::List(_) = attr.meta {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("generated") {
+                generated = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("generator") {
+                let expr: syn::LitStr = meta.value()?.parse()?;
+                generator = Some(expr.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("Expected `generated` or `generator`"))
+            }
+        })?;
+    }
+
+    match (generated, generator) {
+        (None, Some(expr)) => Ok(IdGeneration::Generator(expr)),
+        (None, None) if is_composite => Ok(IdGeneration::Client),
+        (None, None) => Ok(IdGeneration::Database),
+        (Some(value), generator) => match (value.value().as_str(), generator) {
+            ("client", Some(expr)) => Ok(IdGeneration::Generator(expr)),
+            ("client", None) => Ok(IdGeneration::Client),
+            ("database", _) if is_composite => Err(syn::Error::new_spanned(
+                value,
+                "Composite keys cannot be generated by the database",
+            )),
+            ("database", Some(expr)) => Err(syn::Error::new_spanned(
+                expr,
+                "A `generator` cannot be combined with `generated = \"database\"`",
+            )),
+            ("database", None) => Ok(IdGeneration::Database),
+            _ => Err(syn::Error::new_spanned(
+                value,
+                "Expected `generated = \"client\"` or `generated = \"database\"`",
+            )),
+        },
+    }
+}
+
+/// A foreign key declared with `#[crud_belongs_to(Target)]`.
+pub struct BelongsTo<'a> {
+    /// Field holding the foreign key.
+    pub field_ident: &'a syn::Ident,
+    /// Type of the foreign key field, possibly wrapped in `Option`.
+    pub field_type: &'a syn::Type,
+    /// Struct deriving `Read` that the foreign key refers to.
+    pub target: syn::Path,
+    /// Name of the method loading the rows referencing a target, on the target.
+    pub inverse: Option<syn::Ident>,
+}
+
+/// Reads the options of a `#[crud_belongs_to(Target, inverse = "...")]` attribute.
+pub fn parse_belongs_to_options(
+    attr: &syn::Attribute,
+) -> syn::Result<(syn::Path, Option<syn::Ident>)> {
+    attr.parse_args_with(|input: syn::parse::ParseStream| {
+        let target: syn::Path = input.parse()?;
+        let mut inverse = None;
+
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let name: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let value: syn::LitStr = input.parse()?;
+            if name == "inverse" {
+                inverse = Some(value.parse()?);
+            } else {
+                return Err(syn::Error::new_spanned(name, "Expected `inverse`"));
+            }
+        }
+
+        Ok((target, inverse))
+    })
+}
+
+/// A join table declared with `#[crud_many_to_many(Target, through = "...", local = "...", remote = "...")]`.
+pub struct ManyToMany {
+    /// Struct deriving `Read` on the other side of the relation.
+    pub target: syn::Path,
+    /// Name of the join table.
+    pub through: String,
+    /// Join table column referencing the annotated struct.
+    pub local: String,
+    /// Join table column referencing the target.
+    pub remote: String,
+}
+
+/// Reads the options of a `#[crud_many_to_many(...)]` attribute.
+pub fn parse_many_to_many_options(attr: &syn::Attribute) -> syn::Result<ManyToMany> {
+    attr.parse_args_with(|input: syn::parse::ParseStream| {
+        let target: syn::Path = input.parse()?;
+        let mut through = None;
+        let mut local = None;
+        let mut remote = None;
+
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let name: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let value: syn::LitStr = input.parse()?;
+            if name == "through" {
+                through = Some(value.value());
+            } else if name == "local" {
+                local = Some(value.value());
+            } else if name == "remote" {
+                remote = Some(value.value());
+            } else {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "Expected `through`, `local` or `remote`",
+                ));
+            }
+        }
+
+        match (through, local, remote) {
+            (Some(through), Some(local), Some(remote)) => Ok(ManyToMany {
+                target,
+                through,
+                local,
+                remote,
+            }),
+            _ => Err(input.error(
+                "`#[crud_many_to_many]` requires `through`, `local` and `remote` to be specified",
+            )),
+        }
+    })
+}
+
+/// Options of the `#[crud_table("table_name", ...)]` attribute.
+pub struct TableOptions {
+    /// Name of the table as written in the attribute, including its quotes.
+    pub name: String,
+    /// Whether queries are checked against the database at compile time.
+    pub checked: bool,
+}
+
+/// Reads the name and options of a `#[crud_table("table_name", checked = false)]` attribute.
+pub fn parse_table_options(list: &syn::MetaList) -> syn::Result<TableOptions> {
+    let mut segments = vec![proc_macro2::TokenStream::new()];
+    for token in list.tokens.clone() {
+        match &token {
+            proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                segments.push(proc_macro2::TokenStream::new());
+            }
+            _ => segments.last_mut().unwrap().extend([token]),
+        }
+    }
+
+    let mut options = TableOptions {
+        name: segments.remove(0).to_string(),
+        checked: true,
+    };
+    for segment in segments.into_iter().filter(|segment| !segment.is_empty()) {
+        let option: syn::MetaNameValue = syn::parse2(segment)?;
+        match &option.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Bool(checked),
+                ..
+            }) if option.path.is_ident("checked") => options.checked = checked.value,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    option,
+                    "Unknown `#[crud_table]` option, expected `checked = <bool>`",
+                ))
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Constraints declared with `#[crud_column(...)]` on a field.
+#[derive(Default)]
+pub struct ColumnOptions {
+    /// Explicit SQLite type, overriding the one inferred from the field type.
+    pub sql_type: Option<String>,
+    /// Whether the column has a `UNIQUE` constraint.
+    pub unique: bool,
+    /// SQL expression used as the `DEFAULT` value.
+    pub default: Option<String>,
+    /// SQL expression of a `CHECK` constraint.
+    pub check: Option<String>,
+    /// Referenced table and column, written as `"table(column)"`.
+    pub references: Option<(String, String)>,
+}
+
+/// Reads the options of the `#[crud_column(...)]` attribute of a field, if any.
+pub fn parse_column_options(field: &syn::Field) -> syn::Result<ColumnOptions> {
+    let mut options = ColumnOptions::default();
+
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("crud_column") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("unique") {
+                options.unique = true;
+            } else if meta.path.is_ident("sql_type") {
+                options.sql_type = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                options.default = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("check") {
+                options.check = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("references") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                let references = value.value();
+                let parsed = references
+                    .strip_suffix(')')
+                    .and_then(|references| references.split_once('('))
+                    .filter(|(table, column)| !table.is_empty() && !column.is_empty());
+                match parsed {
+                    Some((table, column)) => {
+                        options.references = Some((table.to_string(), column.to_string()))
+                    }
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "Expected `references = \"table(column)\"`",
+                        ))
+                    }
+                }
+            } else {
+                return Err(
+                    meta.error("Expected `sql_type`, `unique`, `default`, `check` or `references`")
+                );
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// How `#[derive(Factory)]` fills a field, declared with `#[factory(...)]`.
+pub enum FactoryValue {
+    /// The value is derived from the field type.
+    Inferred,
+    /// The expression is evaluated with the sequence number bound to `n`.
+    Default(syn::Expr),
+    /// The format string is formatted with the sequence number as `{n}`.
+    Sequence(syn::LitStr),
+}
+
+/// Reads the `#[factory(default = "...")]` or `#[factory(sequence = "...")]`
+/// attribute of a field, if any.
+pub fn parse_factory_value(field: &syn::Field) -> syn::Result<FactoryValue> {
+    let mut value = FactoryValue::Inferred;
+
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("factory") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if !matches!(value, FactoryValue::Inferred) {
+                return Err(meta.error("Only one `#[factory]` value is allowed per field"));
+            }
+
+            if meta.path.is_ident("default") {
+                let expr: syn::LitStr = meta.value()?.parse()?;
+                value = FactoryValue::Default(expr.parse()?);
+            } else if meta.path.is_ident("sequence") {
+                value = FactoryValue::Sequence(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("Expected `default` or `sequence`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(value)
+}
+
+/// Check of a field declared with `#[crud_validate(...)]`.
+pub enum Validation {
+    /// `length(min = 1, max = 255)`, either bound being optional.
+    Length {
+        min: Option<syn::Expr>,
+        max: Option<syn::Expr>,
+    },
+    /// `range(min = 0, max = 150)`, either bound being optional.
+    Range {
+        min: Option<syn::Expr>,
+        max: Option<syn::Expr>,
+    },
+    /// `email`
+    Email,
+    /// `regex = "..."`, checked to be a valid expression.
+    Regex(syn::LitStr),
+    /// `custom = function`, a function taking a reference to the value and
+    /// returning `Result<(), String>`.
+    Custom(syn::Path),
+}
+
+/// Reads the `min` and `max` bounds of a `length(...)` or `range(...)` check.
+fn parse_validation_bounds(
+    meta: &syn::meta::ParseNestedMeta,
+) -> syn::Result<(Option<syn::Expr>, Option<syn::Expr>)> {
+    let mut min = None;
+    let mut max = None;
+    meta.parse_nested_meta(|bound| {
+        if bound.path.is_ident("min") {
+            min = Some(bound.value()?.parse()?);
+        } else if bound.path.is_ident("max") {
+            max = Some(bound.value()?.parse()?);
+        } else {
+            return Err(bound.error("Expected `min` or `max`"));
+        }
+        Ok(())
+    })?;
+
+    match (&min, &max) {
+        (None, None) => Err(meta.error("Expected `min`, `max` or both")),
+        _ => Ok((min, max)),
+    }
+}
+
+/// Reads the checks of every `#[crud_validate(...)]` attribute of a field.
+pub fn parse_validations(field: &syn::Field) -> syn::Result<Vec<Validation>> {
+    let mut validations = Vec::new();
+
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("crud_validate") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("length") {
+                let (min, max) = parse_validation_bounds(&meta)?;
+                validations.push(Validation::Length { min, max });
+            } else if meta.path.is_ident("range") {
+                let (min, max) = parse_validation_bounds(&meta)?;
+                validations.push(Validation::Range { min, max });
+            } else if meta.path.is_ident("email") {
+                validations.push(Validation::Email);
+            } else if meta.path.is_ident("regex") {
+                let pattern: syn::LitStr = meta.value()?.parse()?;
+                if let Err(error) = regex::Regex::new(&pattern.value()) {
+                    return Err(syn::Error::new_spanned(
+                        &pattern,
+                        format!("Invalid regular expression: {error}"),
+                    ));
+                }
+                validations.push(Validation::Regex(pattern));
+            } else if meta.path.is_ident("custom") {
+                validations.push(Validation::Custom(meta.value()?.parse()?));
+            } else {
+                return Err(meta.error("Expected `length`, `range`, `email`, `regex` or `custom`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(validations)
+}
+
+/// Lifecycle hooks enabled with `#[crud_hooks(...)]` on the struct.
+#[derive(Default)]
+pub struct Hooks {
+    pub before_create: bool,
+    pub after_create: bool,
+    pub before_update: bool,
+    pub after_update: bool,
+    pub before_delete: bool,
+    pub after_delete: bool,
+}
+
+/// Reads the hooks listed in a `#[crud_hooks(before_create, after_delete)]`
+/// attribute into `hooks`.
+pub fn parse_hooks_options(attr: &syn::Attribute, hooks: &mut Hooks) -> syn::Result<()> {
+    attr.parse_nested_meta(|meta| {
+        let hook = if meta.path.is_ident("before_create") {
+            &mut hooks.before_create
+        } else if meta.path.is_ident("after_create") {
+            &mut hooks.after_create
+        } else if meta.path.is_ident("before_update") {
+            &mut hooks.before_update
+        } else if meta.path.is_ident("after_update") {
+            &mut hooks.after_update
+        } else if meta.path.is_ident("before_delete") {
+            &mut hooks.before_delete
+        } else if meta.path.is_ident("after_delete") {
+            &mut hooks.after_delete
+        } else {
+            return Err(meta.error(
+                "Expected `before_create`, `after_create`, `before_update`, `after_update`, `before_delete` or `after_delete`",
+            ));
+        };
+        *hook = true;
+        Ok(())
+    })
+}
+
+/// Reads the plural name of a `#[crud_graphql(plural = "people")]` attribute.
+pub fn parse_graphql_options(attr: &syn::Attribute) -> syn::Result<Option<String>> {
+    let mut plural = None;
+    attr.parse_nested_meta(|meta| {
+        if !meta.path.is_ident("plural") {
+            return Err(meta.error("Expected `plural = \"name\"`"));
+        }
+        plural = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+        Ok(())
+    })?;
+    Ok(plural)
+}
+
+/// Returns the SQLite type of a field type, looking through `Option`.
+pub fn sqlite_type_name(ty: &syn::Type) -> Option<&'static str> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+
+    if segment.ident == "Option" || segment.ident == "Vec" {
+        let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return None;
+        };
+        let Some(syn::GenericArgument::Type(inner)) = arguments.args.first() else {
+            return None;
+        };
+        return match segment.ident == "Option" {
+            true => sqlite_type_name(inner),
+            false => match inner {
+                syn::Type::Path(inner_path) if inner_path.path.is_ident("u8") => Some("BLOB"),
+                _ => None,
+            },
+        };
+    }
+
+    match segment.ident.to_string().as_str() {
+        "bool" | "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" => Some("INTEGER"),
+        "f32" | "f64" => Some("REAL"),
+        "String" | "char" => Some("TEXT"),
+        "NaiveDateTime" | "DateTime" | "PrimitiveDateTime" | "OffsetDateTime" => Some("DATETIME"),
+        "NaiveDate" | "Date" => Some("DATE"),
+        "NaiveTime" | "Time" => Some("TIME"),
+        "Uuid" => Some("BLOB"),
+        _ => None,
+    }
+}
+
 #[macro_export]
 macro_rules! parse_struct_fields {
     ($input:expr) => {
@@ -23,8 +491,12 @@ macro_rules! parse_table_attribute {
         for attr in $input.attrs.iter() {
             if attr.path().is_ident("crud_table") {
                 if let syn::Meta::List(value) = &attr.meta {
+                    let options = match $crate::parse_table_options(value) {
+                        Ok(options) => options,
+                        Err(error) => return error.to_compile_error().into(),
+                    };
                     table_name = match table_name {
-                        None => Some(value.tokens.to_string()),
+                        None => Some(options),
                         Some(_) => {
                             return syn::Error::new_spanned(
                                 value,
@@ -55,7 +527,8 @@ macro_rules! parse_table_attribute {
 #[macro_export]
 macro_rules! parse_id_attribute {
     ($fields:expr) => {{
-        let mut id_field_ident: Option<&syn::Ident> = None;
+        let mut id_idents: Vec<&syn::Ident> = Vec::new();
+        let mut id_types: Vec<&syn::Type> = Vec::new();
 
         for field in $fields.iter() {
             let field_ident = match field.ident.as_ref() {
@@ -70,49 +543,153 @@ macro_rules! parse_id_attribute {
                 }
             };
 
+            // Multiple annotated fields form a composite primary key.
             let is_id_field = field
                 .attrs
                 .iter()
                 .any(|attr| attr.path().is_ident("crud_id"));
             if is_id_field {
-                if id_field_ident.is_some() {
+                id_idents.push(field_ident);
+                id_types.push(&field.ty);
+            }
+        }
+
+        if id_idents.is_empty() {
+            return syn::Error::new_spanned(
+                $fields,
+                "A struct field must be annotated with `#[crud_id]` to specify the database table",
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        (id_idents, id_types)
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_column_fields {
+    ($fields:expr) => {{
+        let mut attribute_idents = Vec::new();
+        let mut attribute_types = Vec::new();
+
+        for field in $fields.iter() {
+            let field_ident = match field.ident.as_ref() {
+                Some(ident) => ident,
+                None => {
+                    // If a field does not have a name, emit a compile-time error.
                     return syn::Error::new_spanned(
                         field,
-                        "Only one field can be annotated with `#[crud_id]`",
+                        "All fields in the struct must have names",
                     )
                     .to_compile_error()
                     .into();
                 }
+            };
+            let field_type = &field.ty;
 
-                id_field_ident = Some(field_ident);
+            // Fields managed by the database are not passed in by the caller.
+            let is_managed_field = field.attrs.iter().any(|attr| {
+                attr.path().is_ident("crud_id")
+                    || attr.path().is_ident("crud_created_at")
+                    || attr.path().is_ident("crud_updated_at")
+                    || attr.path().is_ident("crud_version")
+            });
+            if !is_managed_field {
+                attribute_idents.push(field_ident);
+                attribute_types.push(field_type);
             }
         }
 
-        match id_field_ident {
-            Some(ident) => ident,
-            None => {
+        (attribute_idents, attribute_types)
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_optional_field_attribute {
+    ($fields:expr, $attribute:literal) => {{
+        let mut attribute_field_ident: Option<&syn::Ident> = None;
+
+        for field in $fields.iter() {
+            let is_attribute_field = field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident($attribute));
+            if !is_attribute_field {
+                continue;
+            }
+
+            let field_ident = match field.ident.as_ref() {
+                Some(ident) => ident,
+                None => {
+                    return syn::Error::new_spanned(
+                        field,
+                        "All fields in the struct must have names",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            };
+
+            if attribute_field_ident.is_some() {
                 return syn::Error::new_spanned(
-                    $fields,
-                    "A struct field must be annotated with `#[crud_id]` to specify the database table",
+                    field,
+                    concat!("Only one field can be annotated with `#[", $attribute, "]`"),
                 )
                 .to_compile_error()
                 .into();
             }
+
+            attribute_field_ident = Some(field_ident);
         }
+
+        attribute_field_ident
     }};
 }
 
 #[macro_export]
-macro_rules! parse_column_fields {
+macro_rules! parse_id_generation {
     ($fields:expr) => {{
-        let mut attribute_idents = Vec::new();
-        let mut attribute_types = Vec::new();
+        let id_attrs: Vec<&syn::Attribute> = $fields
+            .iter()
+            .filter_map(|field| {
+                field
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path().is_ident("crud_id"))
+            })
+            .collect();
+        let is_composite = id_attrs.len() > 1;
+
+        let mut id_generations = Vec::new();
+        for attr in id_attrs {
+            match $crate::parse_id_generation_options(attr, is_composite) {
+                Ok(generation) => id_generations.push(generation),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        }
+
+        id_generations
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_belongs_to_attributes {
+    ($fields:expr) => {{
+        let mut relations = Vec::new();
 
         for field in $fields.iter() {
+            let Some(attr) = field
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("crud_belongs_to"))
+            else {
+                continue;
+            };
+
             let field_ident = match field.ident.as_ref() {
                 Some(ident) => ident,
                 None => {
-                    // If a field does not have a name, emit a compile-time error.
                     return syn::Error::new_spanned(
                         field,
                         "All fields in the struct must have names",
@@ -121,18 +698,198 @@ macro_rules! parse_column_fields {
                     .into();
                 }
             };
-            let field_type = &field.ty;
 
-            let is_id_field = field
+            match $crate::parse_belongs_to_options(attr) {
+                Ok((target, inverse)) => relations.push($crate::BelongsTo {
+                    field_ident,
+                    field_type: &field.ty,
+                    target,
+                    inverse,
+                }),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        }
+
+        relations
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_many_to_many_attributes {
+    ($input:expr) => {{
+        let mut relations = Vec::new();
+
+        for attr in $input.attrs.iter() {
+            if attr.path().is_ident("crud_many_to_many") {
+                match $crate::parse_many_to_many_options(attr) {
+                    Ok(relation) => relations.push(relation),
+                    Err(error) => return error.to_compile_error().into(),
+                }
+            }
+        }
+
+        relations
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_hooks_attribute {
+    ($input:expr) => {{
+        let mut hooks = $crate::Hooks::default();
+
+        for attr in $input.attrs.iter() {
+            if !attr.path().is_ident("crud_hooks") {
+                continue;
+            }
+
+            if !$input.generics.params.is_empty() {
+                return syn::Error::new_spanned(
+                    attr,
+                    "`#[crud_hooks]` is not supported on generic structs",
+                )
+                .to_compile_error()
+                .into();
+            }
+
+            if let Err(error) = $crate::parse_hooks_options(attr, &mut hooks) {
+                return error.to_compile_error().into();
+            }
+        }
+
+        hooks
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_audit_attribute {
+    ($input:expr) => {{
+        let mut audit_table: Option<syn::LitStr> = None;
+        for attr in $input.attrs.iter() {
+            if !attr.path().is_ident("crud_audit") {
+                continue;
+            }
+
+            if audit_table.is_some() {
+                return syn::Error::new_spanned(attr, "Only one `#[crud_audit]` attribute is allowed")
+                    .to_compile_error()
+                    .into();
+            }
+            match attr.parse_args::<syn::LitStr>() {
+                Ok(table) => audit_table = Some(table),
+                Err(_) => {
+                    return syn::Error::new_spanned(
+                        attr,
+                        "Expected the name of the audit table, as in `#[crud_audit(\"audit_log\")]`",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            }
+        }
+
+        audit_table.map(|table| table.value())
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_outbox_attribute {
+    ($input:expr) => {{
+        let mut outbox_table: Option<syn::LitStr> = None;
+        for attr in $input.attrs.iter() {
+            if !attr.path().is_ident("crud_outbox") {
+                continue;
+            }
+
+            if outbox_table.is_some() {
+                return syn::Error::new_spanned(
+                    attr,
+                    "Only one `#[crud_outbox]` attribute is allowed",
+                )
+                .to_compile_error()
+                .into();
+            }
+            match attr.parse_args::<syn::LitStr>() {
+                Ok(table) => outbox_table = Some(table),
+                Err(_) => {
+                    return syn::Error::new_spanned(
+                        attr,
+                        "Expected the name of the outbox table, as in `#[crud_outbox(\"outbox\")]`",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            }
+        }
+
+        outbox_table.map(|table| table.value())
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_graphql_attribute {
+    ($input:expr) => {{
+        let mut plural: Option<String> = None;
+        for attr in $input.attrs.iter() {
+            if !attr.path().is_ident("crud_graphql") {
+                continue;
+            }
+
+            match $crate::parse_graphql_options(attr) {
+                Ok(name) => plural = name.or(plural),
+                Err(error) => return error.to_compile_error().into(),
+            }
+        }
+
+        plural
+    }};
+}
+
+#[macro_export]
+macro_rules! parse_json_fields {
+    ($input:expr, $fields:expr) => {{
+        let mut json_idents: Vec<&syn::Ident> = Vec::new();
+
+        for field in $fields.iter() {
+            let Some(attr) = field
                 .attrs
                 .iter()
-                .any(|attr| attr.path().is_ident("crud_id"));
-            if !is_id_field {
-                attribute_idents.push(field_ident);
-                attribute_types.push(field_type);
+                .find(|attr| attr.path().is_ident("crud_json"))
+            else {
+                continue;
+            };
+
+            if !matches!(attr.meta, syn::Meta::Path(_)) {
+                return syn::Error::new_spanned(attr, "`#[crud_json]` takes no arguments")
+                    .to_compile_error()
+                    .into();
+            }
+            if field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("crud_id"))
+            {
+                return syn::Error::new_spanned(
+                    attr,
+                    "A `#[crud_id]` field cannot be annotated with `#[crud_json]`",
+                )
+                .to_compile_error()
+                .into();
+            }
+            // Rows are decoded through a struct local to each function, which
+            // cannot name the generic parameters of the deriving struct.
+            if !$input.generics.params.is_empty() {
+                return syn::Error::new_spanned(
+                    attr,
+                    "`#[crud_json]` is not supported on generic structs",
+                )
+                .to_compile_error()
+                .into();
+            }
+            if let Some(ident) = field.ident.as_ref() {
+                json_idents.push(ident);
             }
         }
 
-        (attribute_idents, attribute_types)
+        json_idents
     }};
 }
@@ -1,42 +1,1342 @@
+#[cfg(feature = "graphql")]
+use crud_macro_core::parse_graphql_attribute;
 use crud_macro_core::{
-    parse_column_fields, parse_id_attribute, parse_struct_fields, parse_table_attribute,
+    parse_audit_attribute, parse_belongs_to_attributes, parse_column_fields, parse_column_options,
+    parse_hooks_attribute, parse_id_attribute, parse_id_generation, parse_id_generation_options,
+    parse_json_fields, parse_many_to_many_attributes, parse_optional_field_attribute,
+    parse_outbox_attribute, parse_struct_fields, parse_table_attribute, parse_validations,
+    sqlite_type_name, BelongsTo, IdGeneration, ManyToMany, TableOptions, Validation,
 };
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, Ident};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Ident, Type};
 
-fn build_create_query(id_ident: &Ident, table: &str, attribute_idents: &[&Ident]) -> String {
-    let insert_field_names = attribute_idents
+/// Builds the `WHERE` condition matching every `#[crud_id]` column.
+fn build_id_condition(id_idents: &[&Ident]) -> String {
+    id_idents
+        .iter()
+        .map(|ident| format!("{ident} = ?"))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Builds the type of the `id` parameter and the expressions binding each key column.
+///
+/// A single key is passed as-is, while a composite key is passed as a tuple in
+/// field declaration order.
+fn build_id_param(id_types: &[&Type]) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
+    if let [id_type] = id_types {
+        return (quote! { #id_type }, vec![quote! { id }]);
+    }
+
+    let bindings = (0..id_types.len())
+        .map(|index| {
+            let index = syn::Index::from(index);
+            quote! { id.#index }
+        })
+        .collect();
+
+    (quote! { (#(#id_types),*) }, bindings)
+}
+
+/// Returns the implementation of a `derive_crud::mock` trait if the `mock`
+/// feature is enabled, or nothing otherwise.
+fn build_mock(implementation: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match cfg!(feature = "mock") {
+        true => implementation,
+        false => quote! {},
+    }
+}
+
+/// Wraps the body of a generated function to report its result to the
+/// `derive_crud::observer`, and to run it in a `tracing` span if the
+/// `tracing` feature is enabled.
+///
+/// `rows` computes the number of rows affected from a successful result bound
+/// to `value`, when it can be told from it. Otherwise, the body may assign the
+/// rows written by a statement to `rows_affected`, see [`build_execute`].
+fn build_observed(
+    struct_name: &Ident,
+    table: &str,
+    operation: &str,
+    id: Option<proc_macro2::TokenStream>,
+    rows: Option<proc_macro2::TokenStream>,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let entity = struct_name.to_string();
+    let table = table.trim_matches('"');
+    let rows = match rows {
+        Some(rows) => quote! { Ok(value) => rows_affected.or(Some((#rows) as u64)), },
+        None => quote! { Ok(_) => rows_affected, },
+    };
+
+    let (span, future, record) = match cfg!(feature = "tracing") {
+        true => {
+            let name = format!("{struct_name}::{operation}");
+            let id = match id {
+                Some(id) => quote! { ::derive_crud::tracing::field::debug(&#id) },
+                None => quote! { ::derive_crud::tracing::field::Empty },
+            };
+            (
+                quote! {
+                    let span = ::derive_crud::tracing::info_span!(
+                        #name,
+                        entity = #entity,
+                        table = #table,
+                        operation = #operation,
+                        id = #id,
+                        rows_affected = ::derive_crud::tracing::field::Empty,
+                        error = ::derive_crud::tracing::field::Empty,
+                    );
+                },
+                quote! { ::derive_crud::tracing::Instrument::instrument(async { #body }, span.clone()) },
+                quote! { ::derive_crud::trace::record(&span, &result, rows_affected); },
+            )
+        }
+        false => (quote! {}, quote! { async { #body } }, quote! {}),
+    };
+
+    quote! {
+        let started = ::std::time::Instant::now();
+        #[allow(unused_mut)]
+        let mut rows_affected: Option<u64> = None;
+        #span
+
+        let result: Result<_, ::derive_crud::CRUDError> = #future.await;
+        let rows_affected = match &result {
+            #rows
+            Err(_) => None,
+        };
+        #record
+        ::derive_crud::observer::report(#entity, #table, #operation, started, &result, rows_affected);
+
+        result
+    }
+}
+
+/// Executes a statement, assigning the rows it affected to the
+/// `rows_affected` of [`build_observed`].
+fn build_execute(execution: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        rows_affected = Some(#execution.rows_affected());
+    }
+}
+
+/// Builds the `#[crud_validate]` checks of the given fields, returning a
+/// validation error listing every failed check. `value` returns the
+/// expression of a field, and `Option` fields are only checked when set.
+fn build_validation(
+    fields: &syn::Fields,
+    idents: &[&Ident],
+    value: impl Fn(&Ident) -> proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut checks = Vec::new();
+    for field in fields.iter() {
+        let Some(ident) = field.ident.as_ref().filter(|ident| idents.contains(ident)) else {
+            continue;
+        };
+        let validations = parse_validations(field)?;
+        if validations.is_empty() {
+            continue;
+        }
+
+        let name = ident.to_string();
+        let field_checks = validations.iter().map(|validation| {
+            let bound = |bound: &Option<syn::Expr>| match bound {
+                Some(bound) => quote! { Some(#bound) },
+                None => quote! { None },
+            };
+            match validation {
+                Validation::Length { min, max } => {
+                    let (min, max) = (bound(min), bound(max));
+                    quote! { ::derive_crud::validation::length(&mut errors, #name, value, #min, #max); }
+                }
+                Validation::Range { min, max } => {
+                    let (min, max) = (bound(min), bound(max));
+                    quote! { ::derive_crud::validation::range(&mut errors, #name, value, #min, #max); }
+                }
+                Validation::Email => quote! {
+                    ::derive_crud::validation::email(&mut errors, #name, ::std::convert::AsRef::<str>::as_ref(value));
+                },
+                Validation::Regex(pattern) => quote! {
+                    {
+                        static PATTERN: ::derive_crud::validation::Pattern = ::derive_crud::validation::Pattern::new(#pattern);
+                        ::derive_crud::validation::regex(&mut errors, #name, ::std::convert::AsRef::<str>::as_ref(value), &PATTERN);
+                    }
+                },
+                Validation::Custom(function) => quote! {
+                    ::derive_crud::validation::custom(&mut errors, #name, #function(value));
+                },
+            }
+        });
+
+        let value = value(ident);
+        checks.push(match option_inner_type(&field.ty) {
+            Some(_) => quote! {
+                if let Some(value) = &#value {
+                    #(#field_checks)*
+                }
+            },
+            None => quote! {
+                {
+                    let value = &#value;
+                    #(#field_checks)*
+                }
+            },
+        });
+    }
+
+    if checks.is_empty() {
+        return Ok(quote! {});
+    }
+
+    Ok(quote! {
+        let mut errors = ::std::vec::Vec::new();
+        #(#checks)*
+        if !errors.is_empty() {
+            return Err(::derive_crud::CRUDError::validation(errors));
+        }
+    })
+}
+
+/// Returns the statements acquiring the connection of an operation and
+/// committing it. Operations calling `#[crud_hooks]` or writing to the audit
+/// table of `#[crud_audit]` or the outbox table of `#[crud_outbox]` run in a
+/// transaction, or a savepoint of the transaction of the executor, so that a
+/// failure after the change rolls it back.
+fn build_acquire(transaction: bool) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    match transaction {
+        true => (
+            quote! {
+                let mut connection = executor
+                    .begin()
+                    .await
+                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+            },
+            quote! {
+                connection
+                    .commit()
+                    .await
+                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+            },
+        ),
+        false => (
+            quote! {
+                let mut connection = executor
+                    .acquire()
+                    .await
+                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+            },
+            quote! {},
+        ),
+    }
+}
+
+/// Builds an expression reading the row with the key `id_args` as a JSON
+/// object, or `None` if there is no such row.
+fn build_row_snapshot(
+    table: &TableOptions,
+    fields: &syn::Fields,
+    id_idents: &[&Ident],
+    id_args: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    // `#[crud_json]` columns are nested as JSON rather than as their text.
+    let columns = fields
+        .iter()
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            Some(match is_json_field(field) {
+                true => format!("'{ident}', json({ident})"),
+                false => format!("'{ident}', {ident}"),
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = build_query_scalar(
+        false,
+        quote! { String },
+        &format!(
+            "SELECT json_object({columns}) FROM {} WHERE {}",
+            table.name,
+            build_id_condition(id_idents)
+        ),
+        id_args,
+    );
+
+    quote! {
+        #query
+            .fetch_optional(&mut *connection)
+            .await
+            .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?
+    }
+}
+
+/// Builds the statement writing an audit entry of `operation` with the
+/// `before` and `after` snapshots, see [`build_row_snapshot`].
+fn build_audit_record(
+    audit_table: &str,
+    table: &TableOptions,
+    id_idents: &[&Ident],
+    operation: &str,
+    before: proc_macro2::TokenStream,
+    after: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let table = table.name.trim_matches('"');
+    let id_columns = id_idents.iter().map(|ident| ident.to_string());
+
+    quote! {
+        let audit_before = #before;
+        let audit_after = #after;
+        ::derive_crud::audit::record(
+            &mut *connection,
+            #audit_table,
+            ::derive_crud::audit::AuditEntry {
+                table: #table,
+                id_columns: &[#(#id_columns),*],
+                operation: #operation,
+                before: audit_before,
+                after: audit_after,
+                context,
+            },
+        )
+        .await?;
+    }
+}
+
+/// Builds the statement publishing an event of `operation` to the outbox
+/// table, with the `payload` snapshot, see [`build_row_snapshot`].
+fn build_outbox_publish(
+    outbox_table: &str,
+    table: &TableOptions,
+    id_idents: &[&Ident],
+    operation: &str,
+    payload: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let table = table.name.trim_matches('"');
+    let id_columns = id_idents.iter().map(|ident| ident.to_string());
+
+    quote! {
+        let outbox_payload = #payload;
+        ::derive_crud::outbox::publish(
+            &mut *connection,
+            #outbox_table,
+            ::derive_crud::outbox::OutboxEntry {
+                table: #table,
+                id_columns: &[#(#id_columns),*],
+                operation: #operation,
+                payload: outbox_payload,
+            },
+        )
+        .await?;
+    }
+}
+
+/// Builds the public function `name` of an operation, taking the executor
+/// after the optional `receiver`, then `params`.
+///
+/// With `#[crud_audit]`, the body goes to `<name>_audited`, which also takes
+/// the `context` of the audit entry, and `name` calls it without an actor.
+#[allow(clippy::too_many_arguments)]
+fn build_audited_fn(
+    audited: bool,
+    docs: proc_macro2::TokenStream,
+    name: &str,
+    receiver: Option<proc_macro2::TokenStream>,
+    params: &[proc_macro2::TokenStream],
+    args: &[proc_macro2::TokenStream],
+    output: proc_macro2::TokenStream,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let name_ident = format_ident!("{}", name);
+    if !audited {
+        return quote! {
+            #docs
+            pub async fn #name_ident<'c, A>(#receiver executor: A, #(#params),*) -> Result<#output, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #body
+            }
+        };
+    }
+
+    let audited_ident = format_ident!("{}_audited", name);
+    let audited_doc = format!(
+        "Same as [`{name}`](Self::{name}), writing the change to the audit table with the actor of `context`, in the same transaction."
+    );
+    let call = match receiver {
+        Some(_) => quote! { self.#audited_ident },
+        None => quote! { Self::#audited_ident },
+    };
+
+    quote! {
+        #docs
+        ///
+        /// The change is also written to the audit table, without an actor.
+        pub async fn #name_ident<'c, A>(#receiver executor: A, #(#params),*) -> Result<#output, ::derive_crud::CRUDError>
+        where
+            A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+        {
+            #call(executor, &::derive_crud::audit::AuditContext::default(), #(#args),*).await
+        }
+
+        #[doc = #audited_doc]
+        pub async fn #audited_ident<'c, A>(#receiver executor: A, context: &::derive_crud::audit::AuditContext, #(#params),*) -> Result<#output, ::derive_crud::CRUDError>
+        where
+            A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+        {
+            #body
+        }
+    }
+}
+
+/// Returns the expression of the key of `item` from its `#[crud_id]` fields.
+fn build_item_id(item: proc_macro2::TokenStream, id_idents: &[&Ident]) -> proc_macro2::TokenStream {
+    match id_idents {
+        [ident] => quote! { #item.#ident },
+        idents => quote! { (#(&#item.#idents),*) },
+    }
+}
+
+/// Builds a query decoding rows into `struct_name`.
+///
+/// Checked queries expand to `query_as!`, verified against the database at
+/// compile time, while `#[crud_table(..., checked = false)]` binds the
+/// arguments at runtime and decodes the rows through `sqlx::FromRow`.
+///
+/// The `#[crud_json]` fields of checked queries are decoded as text into a
+/// row struct local to the query, so that `query_as!` still checks their
+/// columns, and then deserialized into the fields of `struct_name`.
+fn build_query_as(
+    checked: bool,
+    struct_name: &Ident,
+    fields: &syn::Fields,
+    sql: &str,
+    args: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    if !checked {
+        return quote! {
+            ::derive_crud::sqlx::query_as::<_, #struct_name>(#sql) #(.bind(&#args))*
+        };
+    }
+    if !fields.iter().any(is_json_field) {
+        return quote! { ::derive_crud::sqlx::query_as!(#struct_name, #sql #(, #args)*) };
+    }
+
+    let row_name = format_ident!("{}Row", struct_name);
+    let row_fields = fields.iter().filter_map(|field| {
+        let ident = field.ident.as_ref()?;
+        let ty = &field.ty;
+        Some(match (is_json_field(field), option_inner_type(ty)) {
+            (false, _) => quote! { #ident: #ty },
+            (true, Some(_)) => quote! { #ident: ::derive_crud::json::NullableText },
+            (true, None) => quote! { #ident: ::derive_crud::json::Text },
+        })
+    });
+    let conversions = fields.iter().filter_map(|field| {
+        let ident = field.ident.as_ref()?;
+        Some(match is_json_field(field) {
+            false => quote! { #ident: row.#ident },
+            true => quote! { #ident: row.#ident.decode()? },
+        })
+    });
+
+    quote! {
+        {
+            struct #row_name {
+                #(#row_fields),*
+            }
+
+            ::derive_crud::sqlx::query_as!(#row_name, #sql #(, #args)*)
+                .try_map(|row: #row_name| Ok(#struct_name { #(#conversions),* }))
+        }
+    }
+}
+
+/// Returns the arguments binding the columns of `idents` to `value(ident)`,
+/// with the statements serializing `#[crud_json]` fields to text beforehand.
+///
+/// `query_as!` borrows its arguments for as long as the query, so the text
+/// is bound to a variable rather than passed as a temporary.
+fn build_column_args(
+    fields: &syn::Fields,
+    idents: &[&Ident],
+    value: impl Fn(&Ident) -> proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
+    let mut bindings = Vec::new();
+    let mut args = Vec::new();
+    for ident in idents {
+        let value = value(ident);
+        let field = fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(*ident));
+        let Some(field) = field.filter(|field| is_json_field(field)) else {
+            args.push(value);
+            continue;
+        };
+
+        let text = format_ident!("{}_json", ident);
+        bindings.push(match option_inner_type(&field.ty) {
+            Some(_) => quote! { let #text = ::derive_crud::json::encode_option(&#value)?; },
+            None => quote! { let #text = ::derive_crud::json::encode(&#value)?; },
+        });
+        args.push(quote! { #text });
+    }
+
+    (quote! { #(#bindings)* }, args)
+}
+
+/// Builds a query without output rows, see [`build_query_as`].
+fn build_query(
+    checked: bool,
+    sql: &str,
+    args: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    match checked {
+        true => quote! { ::derive_crud::sqlx::query!(#sql #(, #args)*) },
+        false => quote! { ::derive_crud::sqlx::query(#sql) #(.bind(&#args))* },
+    }
+}
+
+/// Builds a query returning a single column of `scalar_type`, see [`build_query_as`].
+fn build_query_scalar(
+    checked: bool,
+    scalar_type: proc_macro2::TokenStream,
+    sql: &str,
+    args: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    match checked {
+        true => quote! { ::derive_crud::sqlx::query_scalar!(#sql #(, #args)*) },
+        false => quote! {
+            ::derive_crud::sqlx::query_scalar::<_, #scalar_type>(#sql) #(.bind(&#args))*
+        },
+    }
+}
+
+/// Returns the type as written in the source, without the spacing added by
+/// token stream formatting.
+fn rust_type_name(ty: &Type) -> String {
+    quote!(#ty)
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
+
+/// Returns the `EntityMeta` of a struct as seen by the derive named `derive`,
+/// with `schema` as the table definition of `#[derive(Schema)]`.
+///
+/// The attributes are only inspected here, the derives using them report
+/// their errors.
+fn build_entity_meta(
+    struct_name: &Ident,
+    fields: &syn::Fields,
+    table_name: &str,
+    derive: &str,
+    schema: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let id_attr = |field: &syn::Field| {
+        field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("crud_id"))
+            .cloned()
+    };
+    let id_fields: Vec<_> = fields
+        .iter()
+        .filter(|field| id_attr(field).is_some())
+        .collect();
+    let id_columns = id_fields
+        .iter()
+        .filter_map(|field| Some(field.ident.as_ref()?.to_string()));
+    let id_type = match id_fields.as_slice() {
+        [field] => rust_type_name(&field.ty),
+        _ => format!(
+            "({})",
+            id_fields
+                .iter()
+                .map(|field| rust_type_name(&field.ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let client_generated_id = id_fields
+        .iter()
+        .filter_map(|field| id_attr(field))
+        .any(|attr| {
+            matches!(
+                parse_id_generation_options(&attr, id_fields.len() > 1),
+                Ok(IdGeneration::Client | IdGeneration::Generator(_))
+            )
+        });
+    let field_name = |attribute: &str| {
+        let field = fields.iter().find(|field| {
+            field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident(attribute))
+        });
+        match field.and_then(|field| field.ident.as_ref()) {
+            Some(ident) => {
+                let name = ident.to_string();
+                quote! { Some(#name) }
+            }
+            None => quote! { None },
+        }
+    };
+    let created_at = field_name("crud_created_at");
+    let updated_at = field_name("crud_updated_at");
+    let version = field_name("crud_version");
+
+    let columns = fields.iter().filter_map(|field| {
+        let name = field.ident.as_ref()?.to_string();
+        let rust_type = rust_type_name(&field.ty);
+        let nullable = option_inner_type(&field.ty).is_some();
+        let primary_key = id_attr(field).is_some();
+
+        Some(quote! {
+            ::derive_crud::ColumnMeta {
+                name: #name,
+                rust_type: #rust_type,
+                nullable: #nullable,
+                primary_key: #primary_key,
+            }
+        })
+    });
+    let [create, read, update, delete, schema_derive] =
+        ["create", "read", "update", "delete", "schema"].map(|name| name == derive);
+
+    let name = struct_name.to_string();
+    let table = table_name.trim_matches('"');
+    quote! {
+        ::derive_crud::EntityMeta {
+            name: #name,
+            module: ::std::module_path!(),
+            table: #table,
+            columns: &[#(#columns),*],
+            id_columns: &[#(#id_columns),*],
+            id_type: #id_type,
+            flags: ::derive_crud::EntityFlags {
+                client_generated_id: #client_generated_id,
+                created_at: #created_at,
+                updated_at: #updated_at,
+                version: #version,
+            },
+            derives: ::derive_crud::EntityDerives {
+                create: #create,
+                read: #read,
+                update: #update,
+                delete: #delete,
+                schema: #schema_derive,
+            },
+            schema: #schema,
+        }
+    }
+}
+
+/// Returns the submission of `meta` to the entity registry. Generic structs
+/// have no single description to register.
+fn build_entity_registration(
+    generics: &syn::Generics,
+    meta: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if !generics.params.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        ::derive_crud::inventory::submit! {
+            ::derive_crud::EntityRegistration(&#meta)
+        }
+    }
+}
+
+/// Returns `T` when the given type is written as `Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        syn::GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Converts a snake_case name to PascalCase.
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Builds the loaders of a `#[crud_belongs_to(Target)]` foreign key.
+///
+/// The referencing struct gets an accessor named after the field without its
+/// `_id` suffix and a batched `for_<accessor>s` loader. The inverse loader,
+/// returning every entry referencing a target, is declared by an extension
+/// trait implemented for `Target`, so that `Target` may be foreign.
+fn build_belongs_to_loaders(
+    vis: &syn::Visibility,
+    struct_name: &Ident,
+    generics: &syn::Generics,
+    table: &TableOptions,
+    fields: &syn::Fields,
+    relation: &BelongsTo,
+) -> proc_macro2::TokenStream {
+    let field_ident = relation.field_ident;
+    let target = &relation.target;
+
+    let field_name = field_ident.to_string();
+    let accessor_name = field_name.strip_suffix("_id").unwrap_or(&field_name);
+    let accessor = format_ident!("{}", accessor_name);
+    let batch_loader = format_ident!("for_{}s", accessor_name);
+    let target_name = match target.segments.last() {
+        Some(segment) => segment.ident.to_string(),
+        None => return quote! {},
+    };
+    let struct_snake = to_snake_case(&struct_name.to_string());
+    let inverse = relation.inverse.clone().unwrap_or_else(|| {
+        match accessor_name == to_snake_case(&target_name) {
+            true => format_ident!("{}s", struct_snake),
+            false => format_ident!("{}_{}s", accessor_name, struct_snake),
+        }
+    });
+    let inverse_trait = format_ident!("{}{}", target_name, to_pascal_case(&inverse.to_string()));
+    let trait_doc = format!(" Reads the `{struct_name}` entries referencing a `{target_name}`.");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (key_type, accessor_fn) = match option_inner_type(relation.field_type) {
+        Some(inner) => (
+            inner,
+            quote! {
+                /// Reads the entry referenced by this foreign key, if it is set.
+                pub async fn #accessor<'c, A>(&self, executor: A) -> Result<Option<#target>, ::derive_crud::CRUDError>
+                where
+                    A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+                {
+                    match self.#field_ident.clone() {
+                        Some(id) => #target::read_one(executor, id).await.map(Some),
+                        None => Ok(None),
+                    }
+                }
+            },
+        ),
+        None => (
+            relation.field_type,
+            quote! {
+                /// Reads the entry referenced by this foreign key.
+                pub async fn #accessor<'c, A>(&self, executor: A) -> Result<#target, ::derive_crud::CRUDError>
+                where
+                    A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+                {
+                    #target::read_one(executor, self.#field_ident.clone()).await
+                }
+            },
+        ),
+    };
+
+    let inverse_query = build_query_as(
+        table.checked,
+        struct_name,
+        fields,
+        &format!("SELECT * FROM {} WHERE {field_ident} = ?", table.name),
+        &[quote! { id }],
+    );
+    let batch_query = build_query_as(
+        table.checked,
+        struct_name,
+        fields,
+        &format!(
+            "SELECT * FROM {} WHERE {field_ident} IN (SELECT value FROM json_each(?))",
+            table.name
+        ),
+        &[quote! { ids }],
+    );
+
+    let batch_loader_body = build_observed(
+        struct_name,
+        &table.name,
+        &batch_loader.to_string(),
+        Some(quote! { ids }),
+        Some(quote! { value.len() }),
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let ids = ::derive_crud::serde_json::to_string(ids)
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let items: ::std::vec::Vec<Self> = #batch_query
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            Ok(items)
+        },
+    );
+
+    let inverse_body = build_observed(
+        struct_name,
+        &table.name,
+        &inverse.to_string(),
+        Some(quote! { ::derive_crud::Entity::id(self) }),
+        Some(quote! { value.len() }),
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let id: #key_type = ::derive_crud::Entity::id(self);
+
+            let items: ::std::vec::Vec<#struct_name #ty_generics> = #inverse_query
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            Ok(items)
+        },
+    );
+
+    quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #accessor_fn
+
+            /// Reads every entry referencing one of the given keys.
+            pub async fn #batch_loader<'c, A>(executor: A, ids: &[#key_type]) -> Result<::std::vec::Vec<Self>, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #batch_loader_body
+            }
+        }
+
+        #[doc = #trait_doc]
+        #vis trait #inverse_trait #generics #where_clause {
+            /// Reads every entry referencing this one.
+            fn #inverse<'c, A>(&self, executor: A) -> impl ::std::future::Future<Output = Result<::std::vec::Vec<#struct_name #ty_generics>, ::derive_crud::CRUDError>> + Send
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite> + Send + 'c;
+        }
+
+        impl #impl_generics #inverse_trait #ty_generics for #target #where_clause {
+            async fn #inverse<'c, A>(&self, executor: A) -> Result<::std::vec::Vec<#struct_name #ty_generics>, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite> + Send + 'c,
+            {
+                #inverse_body
+            }
+        }
+    }
+}
+
+/// Builds the loaders and writers of a `#[crud_many_to_many(Target, ...)]` join table.
+///
+/// Methods are named after the target, so `Tag` generates `tags()`,
+/// `add_tag()`, `remove_tag()` and `set_tags()`.
+fn build_many_to_many_methods(
+    struct_name: &Ident,
+    checked: bool,
+    relation: &ManyToMany,
+) -> proc_macro2::TokenStream {
+    let target = &relation.target;
+    let (through, local, remote) = (&relation.through, &relation.local, &relation.remote);
+
+    let target_name = match target.segments.last() {
+        Some(segment) => to_snake_case(&segment.ident.to_string()),
+        None => return quote! {},
+    };
+    let loader = format_ident!("{}s", target_name);
+    let adder = format_ident!("add_{}", target_name);
+    let remover = format_ident!("remove_{}", target_name);
+    let setter = format_ident!("set_{}s", target_name);
+
+    let remote_column = match checked {
+        true => format!("{remote} AS \"{remote}!\""),
+        false => remote.to_string(),
+    };
+    let select_query = build_query_scalar(
+        checked,
+        quote! { <#target as ::derive_crud::Entity>::Id },
+        &format!("SELECT {remote_column} FROM {through} WHERE {local} = ?"),
+        &[quote! { id }],
+    );
+    let insert_query = build_query(
+        checked,
+        &format!("INSERT INTO {through} ({local}, {remote}) VALUES (?, ?)"),
+        &[quote! { local_id }, quote! { id }],
+    );
+    let delete_query = build_query(
+        checked,
+        &format!("DELETE FROM {through} WHERE {local} = ? AND {remote} = ?"),
+        &[quote! { local_id }, quote! { id }],
+    );
+    let clear_query = build_query(
+        checked,
+        &format!("DELETE FROM {through} WHERE {local} = ?"),
+        &[quote! { local_id }],
+    );
+    let insert_many_query = build_query(
+        checked,
+        &format!("INSERT INTO {through} ({local}, {remote}) SELECT ?, value FROM json_each(?)"),
+        &[quote! { local_id }, quote! { ids }],
+    );
+
+    let loader_body = build_observed(
+        struct_name,
+        through,
+        &loader.to_string(),
+        Some(quote! { ::derive_crud::Entity::id(self) }),
+        Some(quote! { value.len() }),
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let id = ::derive_crud::Entity::id(self);
+
+            let ids = #select_query
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            #target::read_many(&mut *connection, &ids).await
+        },
+    );
+
+    let insert_execution = build_execute(quote! {
+        #insert_query
+            .execute(&mut *connection)
+            .await
+            .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?
+    });
+    let adder_body = build_observed(
+        struct_name,
+        through,
+        &adder.to_string(),
+        Some(quote! { ::derive_crud::Entity::id(self) }),
+        None,
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let local_id = ::derive_crud::Entity::id(self);
+
+            #insert_execution
+
+            Ok(())
+        },
+    );
+
+    let delete_execution = build_execute(quote! {
+        #delete_query
+            .execute(&mut *connection)
+            .await
+            .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?
+    });
+    let remover_body = build_observed(
+        struct_name,
+        through,
+        &remover.to_string(),
+        Some(quote! { ::derive_crud::Entity::id(self) }),
+        None,
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let local_id = ::derive_crud::Entity::id(self);
+
+            #delete_execution
+
+            Ok(())
+        },
+    );
+
+    let setter_body = build_observed(
+        struct_name,
+        through,
+        &setter.to_string(),
+        Some(quote! { ::derive_crud::Entity::id(self) }),
+        None,
+        quote! {
+            let local_id = ::derive_crud::Entity::id(self);
+            let ids = ::derive_crud::serde_json::to_string(ids)
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let mut transaction = executor
+                .begin()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            #clear_query
+                .execute(&mut *transaction)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            #insert_many_query
+                .execute(&mut *transaction)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            transaction
+                .commit()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))
+        },
+    );
+
+    quote! {
+        impl #struct_name {
+            /// Reads every entry linked to this one through the join table.
+            pub async fn #loader<'c, A>(&self, executor: A) -> Result<::std::vec::Vec<#target>, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #loader_body
+            }
+
+            /// Links an entry to this one through the join table.
+            pub async fn #adder<'c, A>(&self, executor: A, id: <#target as ::derive_crud::Entity>::Id) -> Result<(), ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #adder_body
+            }
+
+            /// Unlinks an entry from this one in the join table.
+            pub async fn #remover<'c, A>(&self, executor: A, id: <#target as ::derive_crud::Entity>::Id) -> Result<(), ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #remover_body
+            }
+
+            /// Replaces every link of this entry in the join table within a single transaction.
+            pub async fn #setter<'c, A>(&self, executor: A, ids: &[<#target as ::derive_crud::Entity>::Id]) -> Result<(), ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #setter_body
+            }
+        }
+    }
+}
+
+/// Returns whether the field is stored as JSON with `#[crud_json]`.
+fn is_json_field(field: &syn::Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("crud_json"))
+}
+
+/// Returns the fields whose type is `Option<T>`.
+fn optional_field_idents(fields: &syn::Fields) -> Vec<&Ident> {
+    fields
+        .iter()
+        .filter(|field| option_inner_type(&field.ty).is_some())
+        .filter_map(|field| field.ident.as_ref())
+        .collect()
+}
+
+/// Builds the `{Struct}Filter` of a struct, with its `Filterable` and
+/// `PageRepository` implementations, and the `read_page` function listing the
+/// rows matching a filter.
+fn build_read_page(
+    vis: &syn::Visibility,
+    struct_name: &Ident,
+    table: &TableOptions,
+    fields: &syn::Fields,
+    id_idents: &[&Ident],
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let filter_name = format_ident!("{}Filter", struct_name);
+    let field_idents: Vec<_> = fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+    // JSON text is not compared, so `#[crud_json]` fields are not filtered on.
+    let filter_idents: Vec<_> = fields
+        .iter()
+        .filter(|field| !is_json_field(field))
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+    let filter_fields = fields
+        .iter()
+        .filter(|field| !is_json_field(field))
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            let ty = option_inner_type(&field.ty).unwrap_or(&field.ty);
+            let doc = format!(" Matches the rows whose `{ident}` equals this value.");
+            Some(quote! {
+                #[doc = #doc]
+                pub #ident: Option<#ty>
+            })
+        });
+    let filter_doc =
+        format!(" Filter of the rows of [`{struct_name}`] listed by `{struct_name}::read_page`.");
+    let deserialize = match cfg!(feature = "serde") {
+        true => quote! {
+            #[derive(::derive_crud::serde::Deserialize)]
+            #[serde(crate = "::derive_crud::serde")]
+        },
+        false => quote! {},
+    };
+
+    let conditions = filter_idents
+        .iter()
+        .map(|ident| format!("(? IS NULL OR {ident} = ?)"))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let mut args: Vec<_> = filter_idents
+        .iter()
+        .flat_map(|ident| [quote! { filter.#ident }, quote! { filter.#ident }])
+        .collect();
+    args.extend([quote! { page.limit }, quote! { page.offset }]);
+    let order = id_idents
+        .iter()
+        .map(|ident| ident.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = build_query_as(
+        table.checked,
+        struct_name,
+        fields,
+        &format!(
+            "SELECT {} FROM {} WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            build_output_columns(&field_idents, &optional_field_idents(fields), table.checked),
+            table.name,
+            conditions,
+            order,
+        ),
+        &args,
+    );
+    let body = build_observed(
+        struct_name,
+        &table.name,
+        "read_page",
+        None,
+        Some(quote! { value.len() }),
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let items: ::std::vec::Vec<#struct_name> = #query
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            Ok(items)
+        },
+    );
+
+    (
+        quote! {
+            #[doc = #filter_doc]
+            #[derive(Default)]
+            #deserialize
+            #vis struct #filter_name {
+                #(#filter_fields),*
+            }
+
+            impl ::derive_crud::Filterable for #struct_name {
+                type Filter = #filter_name;
+            }
+
+            impl ::derive_crud::PageRepository<#struct_name> for ::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite> {
+                async fn read_page(&self, filter: &#filter_name, page: ::derive_crud::Page) -> Result<::std::vec::Vec<#struct_name>, ::derive_crud::CRUDError> {
+                    #struct_name::read_page(self, filter, page).await
+                }
+            }
+        },
+        quote! {
+            /// Reads the entries matching `filter` within `page`, in ID order.
+            ///
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
+            pub async fn read_page<'c, A>(executor: A, filter: &#filter_name, page: ::derive_crud::Page) -> Result<::std::vec::Vec<Self>, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #body
+            }
+        },
+    )
+}
+
+/// Builds a `RETURNING` or `SELECT` column list.
+///
+/// SQLite hides the nullability of output columns once foreign keys are checked
+/// or keys are looked up through a subquery, so with `mark_non_null` columns of
+/// non-`Option` fields are marked as non-null. Other queries, and unchecked
+/// ones which decode columns by name, keep the plain column names.
+fn build_output_columns(
+    idents: &[&Ident],
+    optional_idents: &[&Ident],
+    mark_non_null: bool,
+) -> String {
+    idents
+        .iter()
+        .map(
+            |ident| match !mark_non_null || optional_idents.contains(ident) {
+                true => ident.to_string(),
+                false => format!("{ident} AS \"{ident}!\""),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Builds the `RETURNING` list of a write to the table of the struct.
+///
+/// SQLite only hides the nullability of the returned columns when the write
+/// checks foreign keys, so the columns are only marked for structs declaring a
+/// `#[crud_belongs_to]` or `#[crud_column(references = ...)]` foreign key.
+fn build_returning_columns(
+    table: &TableOptions,
+    fields: &syn::Fields,
+    idents: &[&Ident],
+) -> String {
+    let foreign_keys = fields.iter().any(|field| {
+        field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("crud_belongs_to"))
+            || parse_column_options(field).is_ok_and(|options| options.references.is_some())
+    });
+
+    build_output_columns(
+        idents,
+        &optional_field_idents(fields),
+        table.checked && foreign_keys,
+    )
+}
+
+fn build_create_query(
+    id_idents: &[&Ident],
+    table: &TableOptions,
+    attribute_idents: &[&Ident],
+    generated_columns: &[(&Ident, &str)],
+    fields: &syn::Fields,
+) -> String {
+    let mut insert_idents = attribute_idents.to_vec();
+    insert_idents.extend(generated_columns.iter().map(|(ident, _)| *ident));
+
+    let mut insert_placeholders = vec!["?"; attribute_idents.len()];
+    insert_placeholders.extend(generated_columns.iter().map(|(_, value)| *value));
+
+    let insert_field_names = insert_idents
         .iter()
         .map(|ident| ident.to_string())
         .collect::<Vec<_>>();
 
-    let insert_placeholders = vec!["?"; attribute_idents.len()];
-
-    let mut return_field_names: Vec<_> = vec![id_ident.to_string()];
-    return_field_names.extend(attribute_idents.iter().map(|ident| ident.to_string()));
+    // Key columns that are not inserted are assigned by the database.
+    let mut return_idents: Vec<_> = id_idents
+        .iter()
+        .filter(|ident| !attribute_idents.contains(ident))
+        .copied()
+        .collect();
+    return_idents.extend(insert_idents);
 
     format!(
         "INSERT INTO {} ({}) VALUES ({}) RETURNING {}",
-        table,
+        table.name,
         insert_field_names.join(", "),
         insert_placeholders.join(", "),
-        return_field_names.join(", ")
+        build_returning_columns(table, fields, &return_idents)
     )
 }
 
-fn build_update_query(id_ident: &Ident, table: &str, attribute_idents: &[&Ident]) -> String {
-    let set_clauses: Vec<_> = attribute_idents
+fn build_update_query(
+    id_idents: &[&Ident],
+    table: &TableOptions,
+    attribute_idents: &[&Ident],
+    created_at_ident: Option<&Ident>,
+    updated_at_ident: Option<&Ident>,
+    version_ident: Option<&Ident>,
+    fields: &syn::Fields,
+) -> String {
+    let mut set_clauses: Vec<_> = attribute_idents
         .iter()
         .map(|ident| format!("{ident} = ?"))
         .collect();
+    if let Some(ident) = updated_at_ident {
+        set_clauses.push(format!("{ident} = CURRENT_TIMESTAMP"));
+    }
+    if let Some(ident) = version_ident {
+        set_clauses.push(format!("{ident} = {ident} + 1"));
+    }
 
-    format!(
-        "UPDATE {} SET {} WHERE {} = ?",
-        table,
+    let mut where_clauses = vec![build_id_condition(id_idents)];
+    if let Some(ident) = version_ident {
+        where_clauses.push(format!("{ident} = ?"));
+    }
+
+    let query = format!(
+        "UPDATE {} SET {} WHERE {}",
+        table.name,
         set_clauses.join(", "),
-        id_ident,
+        where_clauses.join(" AND "),
+    );
+
+    let managed_idents: Vec<_> = created_at_ident
+        .into_iter()
+        .chain(updated_at_ident)
+        .chain(version_ident)
+        .collect();
+    if managed_idents.is_empty() {
+        return query;
+    }
+
+    // Columns written by the database are returned so the struct can be refreshed.
+    let mut return_idents = id_idents.to_vec();
+    return_idents.extend(attribute_idents);
+    return_idents.extend(managed_idents);
+
+    format!(
+        "{} RETURNING {}",
+        query,
+        build_returning_columns(table, fields, &return_idents)
     )
 }
 
@@ -50,16 +1350,42 @@ fn build_update_query(id_ident: &Ident, table: &str, attribute_idents: &[&Ident]
 /// # Attributes
 ///
 /// - `#[crud_id]`: Primary key for the database table. This attribute must be
-///   placed on a single named field within the struct.
+///   placed on at least one named field within the struct. Annotating several
+///   fields declares a composite key.
+/// - `#[crud_id(generated = "client")]`: The key is passed to `create` instead
+///   of being assigned by SQLite. Adding `generator = "uuid::Uuid::now_v7()"`
+///   evaluates the expression inside `create` instead. Composite keys are always
+///   generated by the client.
 /// - `#[crud_table("table_name")]`: Name of the database table that the struct
 ///   is meant to represent. This attribute must be placed on the struct itself.
+///   With `#[crud_table("table_name", checked = false)]`, queries are not
+///   checked against a database at compile time, and rows are decoded through
+///   `sqlx::FromRow`, which the struct must derive.
+/// - `#[crud_created_at]` / `#[crud_updated_at]`: Optional audit timestamp
+///   columns. They are set to `CURRENT_TIMESTAMP` by the database, excluded from
+///   the parameters of `create`, and returned with the stored values.
+/// - `#[crud_version]`: Optional optimistic locking column, initialized to `1`
+///   and excluded from the parameters of `create`.
+/// - `#[crud_hooks(before_create, after_create)]`: Calls the `BeforeCreate`
+///   hook with the parameters, gathered in a generated `New<Struct>`, before
+///   inserting the row, and the `AfterCreate` hook with the inserted row.
+/// - `#[crud_validate(...)]`: Checks the parameter before inserting the row,
+///   see `#[derive(Update)]` for the checks.
+/// - `#[crud_audit("audit_table")]`: Writes every insertion to the audit
+///   table in the same transaction, and generates `create_audited`, taking the
+///   `derive_crud::audit::AuditContext` of the change after the executor.
+/// - `#[crud_outbox("outbox_table")]`: Publishes an event with the inserted
+///   row to the outbox table in the same transaction, read with
+///   `derive_crud::outbox::OutboxConsumer` (`outbox-consumer` feature).
+/// - `#[crud_json]`: Stores the field as JSON in a TEXT column, serializing
+///   it with serde on insertion and deserializing the returned row.
 ///
 /// # Panics (Compile-time Errors)
 ///
 /// This macro will cause a compile-time error if:
 /// - It is applied to an enum or union (only structs are supported).
 /// - The struct is not annotated with `#[crud_table("table_name")]`.
-/// - A single field is not annotated with `#[crud_id]`.
+/// - No field is annotated with `#[crud_id]`.
 /// - The struct does not accurately match the database schema for the table.
 ///
 /// # Example
@@ -80,71 +1406,1091 @@ fn build_update_query(id_ident: &Ident, table: &str, attribute_idents: &[&Ident]
 ///    let result = Test::create("Debbie".to_string(), "debbie@hotmail.com", 47).await;
 /// }
 /// ```
-#[proc_macro_derive(Create, attributes(crud_id, crud_table))]
-pub fn create_derive(input: TokenStream) -> TokenStream {
+#[proc_macro_derive(
+    Create,
+    attributes(
+        crud_id,
+        crud_table,
+        crud_created_at,
+        crud_updated_at,
+        crud_version,
+        crud_belongs_to,
+        crud_many_to_many,
+        crud_hooks,
+        crud_audit,
+        crud_outbox,
+        crud_validate,
+        crud_json
+    )
+)]
+pub fn create_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = parse_struct_fields!(input);
+
+    let table = parse_table_attribute!(input);
+    let (id_idents, id_types) = parse_id_attribute!(fields);
+    parse_json_fields!(input, fields);
+    let (column_idents, column_types) = parse_column_fields!(fields);
+    let id_generations = parse_id_generation!(fields);
+    let created_at_ident = parse_optional_field_attribute!(fields, "crud_created_at");
+    let updated_at_ident = parse_optional_field_attribute!(fields, "crud_updated_at");
+    let version_ident = parse_optional_field_attribute!(fields, "crud_version");
+    let hooks = parse_hooks_attribute!(input);
+    let audit_table = parse_audit_attribute!(input);
+    let outbox_table = parse_outbox_attribute!(input);
+    let generated_columns: Vec<_> = created_at_ident
+        .into_iter()
+        .chain(updated_at_ident)
+        .map(|ident| (ident, "CURRENT_TIMESTAMP"))
+        .chain(version_ident.map(|ident| (ident, "1")))
+        .collect();
+
+    let mut param_idents = Vec::new();
+    let mut param_types = Vec::new();
+    let mut inserted_idents = Vec::new();
+    let mut id_generators = Vec::new();
+    for ((ident, ty), generation) in id_idents.iter().zip(&id_types).zip(&id_generations) {
+        match generation {
+            IdGeneration::Database => {}
+            IdGeneration::Client => {
+                param_idents.push(*ident);
+                param_types.push(*ty);
+                inserted_idents.push(*ident);
+            }
+            IdGeneration::Generator(expr) => {
+                id_generators.push(quote! { let #ident: #ty = #expr; });
+                inserted_idents.push(*ident);
+            }
+        }
+    }
+    param_idents.extend(column_idents.iter().copied());
+    param_types.extend(column_types.iter().copied());
+    inserted_idents.extend(column_idents.iter().copied());
+
+    let fn_params: Vec<_> = param_idents
+        .iter()
+        .zip(param_types.iter())
+        .map(|(ident, ty)| {
+            quote! { #ident: #ty }
+        })
+        .collect();
+
+    let query = build_create_query(
+        &id_idents,
+        &table,
+        &inserted_idents,
+        &generated_columns,
+        fields,
+    );
+    let (json_bindings, inserted_args) =
+        build_column_args(fields, &inserted_idents, |ident| quote! { #ident });
+    let query = build_query_as(table.checked, struct_name, fields, &query, &inserted_args);
+
+    let repository = if input.generics.params.is_empty() {
+        let mock_ids = id_idents.iter().zip(&id_types).zip(&id_generations).map(
+            |((ident, ty), generation)| match generation {
+                IdGeneration::Database => quote! {
+                    self.#ident = <#ty as ::std::convert::TryFrom<i64>>::try_from(sequence)
+                        .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+                },
+                IdGeneration::Client => quote! {},
+                IdGeneration::Generator(expr) => quote! { self.#ident = #expr; },
+            },
+        );
+        let mock_version = version_ident.map(|ident| quote! { self.#ident = 1; });
+        let mock = build_mock(quote! {
+            impl ::derive_crud::mock::MockCreate for #struct_name {
+                #[allow(unused_variables)]
+                fn prepare_create(&mut self, sequence: i64) -> Result<(), ::derive_crud::CRUDError> {
+                    #(#mock_ids)*
+                    #mock_version
+
+                    Ok(())
+                }
+            }
+        });
+
+        quote! {
+            impl ::derive_crud::CreateRepository<#struct_name> for ::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite> {
+                async fn create(&self, item: #struct_name) -> Result<#struct_name, ::derive_crud::CRUDError> {
+                    #struct_name::create(self, #(item.#param_idents),*).await
+                }
+            }
+
+            #mock
+        }
+    } else {
+        quote! {}
+    };
+
+    // The hook may change the parameters, which are gathered in a
+    // `New<Struct>` for it.
+    let (values, before_create) = match hooks.before_create {
+        true => {
+            let vis = &input.vis;
+            let values_name = format_ident!("New{}", struct_name);
+            let values_doc = format!(
+                "Values of a new `{struct_name}` passed to its `BeforeCreate` hook, generated by `#[derive(Create)]`."
+            );
+            (
+                quote! {
+                    #[doc = #values_doc]
+                    #vis struct #values_name {
+                        #(pub #param_idents: #param_types,)*
+                    }
+
+                    impl ::derive_crud::CreateValues for #struct_name {
+                        type Values = #values_name;
+                    }
+                },
+                quote! {
+                    let mut values = #values_name { #(#param_idents),* };
+                    <Self as ::derive_crud::BeforeCreate>::before_create(&mut *connection, &mut values).await?;
+                    let #values_name { #(#param_idents),* } = values;
+                },
+            )
+        }
+        false => (quote! {}, quote! {}),
+    };
+    let validation = match build_validation(fields, &param_idents, |ident| quote! { #ident }) {
+        Ok(validation) => validation,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let after_create = hooks.after_create.then(|| {
+        quote! { ::derive_crud::AfterCreate::after_create(&item, &mut *connection).await?; }
+    });
+
+    // The key is only known once the row is inserted.
+    let record_id = match cfg!(feature = "tracing") {
+        true => {
+            let item_id = build_item_id(quote! { item }, &id_idents);
+            quote! {
+                ::derive_crud::tracing::Span::current()
+                    .record("id", ::derive_crud::tracing::field::debug(&#item_id));
+            }
+        }
+        false => quote! {},
+    };
+    let (acquire, commit) = build_acquire(
+        hooks.before_create
+            || hooks.after_create
+            || audit_table.is_some()
+            || outbox_table.is_some(),
+    );
+    let item_ids: Vec<_> = id_idents
+        .iter()
+        .map(|ident| quote! { item.#ident })
+        .collect();
+    let audit = audit_table.as_ref().map(|audit_table| {
+        let after = build_row_snapshot(&table, fields, &id_idents, &item_ids);
+        build_audit_record(
+            audit_table,
+            &table,
+            &id_idents,
+            "create",
+            quote! { None },
+            after,
+        )
+    });
+    let outbox = outbox_table.as_ref().map(|outbox_table| {
+        let payload = build_row_snapshot(&table, fields, &id_idents, &item_ids);
+        build_outbox_publish(outbox_table, &table, &id_idents, "create", payload)
+    });
+    let create_body = build_observed(
+        struct_name,
+        &table.name,
+        "create",
+        None,
+        Some(quote! { 1 }),
+        quote! {
+            #acquire
+
+            #before_create
+            #validation
+            #(#id_generators)*
+            #json_bindings
+
+            let item = #query
+                .fetch_one(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+            #record_id
+            #audit
+            #outbox
+            #after_create
+            #commit
+
+            Ok(item)
+        },
+    );
+    let param_args: Vec<_> = param_idents.iter().map(|ident| quote! { #ident }).collect();
+    let create_fn = build_audited_fn(
+        audit_table.is_some(),
+        quote! {
+            /// Creates a new entry in the database.
+            ///
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to insert into.
+            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
+        },
+        "create",
+        None,
+        &fn_params,
+        &param_args,
+        quote! { Self },
+        create_body,
+    );
+    let registration = build_entity_registration(
+        &input.generics,
+        build_entity_meta(struct_name, fields, &table.name, "create", quote! { None }),
+    );
+
+    quote! {
+        #registration
+        #repository
+        #values
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #create_fn
+        }
+    }
+    .into()
+}
+
+/// Implements a CRUD functions to read items from the database.
+///
+/// Generates functions that read one/multiple entries in the database table with a
+/// given ID, several entries by their IDs with `read_many` (single-column keys
+/// only), or to read all entries at once. The struct also implements
+/// [`Entity`](../derive_crud/trait.Entity.html) to expose its table and key,
+/// and non-generic structs are listed by `derive_crud::registered_entities()`.
+///
+/// With the `page` feature, non-generic structs also get `read_page`, listing
+/// the entries matching a generated `{Struct}Filter` one `derive_crud::Page` at
+/// a time, in ID order.
+/// The filter has an `Option` of every field, and only compares the fields
+/// which are set. With the `serde` feature, it can be deserialized from a
+/// query string.
+///
+///
+/// # Attributes
+///
+/// - `#[crud_id]`: Primary key for the database table. This attribute must be
+///   placed on at least one named field within the struct. Annotating several
+///   fields declares a composite key.
+/// - `#[crud_table("table_name")]`: Name of the database table that the struct
+///   is meant to represent. This attribute must be placed on the struct itself.
+///   With `#[crud_table("table_name", checked = false)]`, queries are not
+///   checked against a database at compile time, and rows are decoded through
+///   `sqlx::FromRow`, which the struct must derive.
+/// - `#[crud_belongs_to(Target)]`: Marks a foreign key referencing `Target`,
+///   which must also derive `Read`. For a field `customer_id`, this generates
+///   `customer()` reading the referenced entry and `for_customers()` reading
+///   every entry referencing one of several keys. For an `Order` struct, the
+///   `CustomerOrders` trait, implemented for `Target`, adds `orders()` reading
+///   every entry referencing a `Target`. Another foreign key, like
+///   `reviewer_id`, names it `reviewer_orders()` instead, and
+///   `#[crud_belongs_to(Target, inverse = "name")]` renames it.
+/// - `#[crud_many_to_many(Target, through = "join_table", local = "column", remote = "column")]`:
+///   Links the struct to `Target` through a join table. For a `Tag` target, this
+///   generates `tags()`, `add_tag()`, `remove_tag()` and `set_tags()`, the latter
+///   replacing every link within a transaction. This attribute must be placed on
+///   the struct itself, and both sides must derive `Read` with a single `#[crud_id]`.
+/// - `#[crud_json]`: Deserializes the field from the JSON text of its column,
+///   which is still checked to hold text. The field is left out of the filter
+///   of `read_page`. Unchecked tables decode rows through `sqlx::FromRow`, so
+///   the field must also be marked `#[sqlx(json)]`, or `#[sqlx(json(nullable))]`
+///   for an `Option`.
+///
+/// # Panics (Compile-time Errors)
+///
+/// This macro will cause a compile-time error if:
+/// - It is applied to an enum or union (only structs are supported).
+/// - The struct is not annotated with `#[crud_table("table_name")]`.
+/// - No field is annotated with `#[crud_id]`.
+/// - The struct does not accurately match the database schema for the table.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Read)]
+/// #[crud_table("users")]
+/// struct User {
+///    #[crud_id]
+///   id: i64,
+///   name: String,
+///   email: String,
+///   age: i32,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///    let result = Test::read_one(1).await;
+///    let results = Test::read(1).await;
+///    let everything = Test::read_all().await;
+///    let filter = UserFilter { age: Some(42), ..Default::default() };
+///    let page = Test::read_page(&filter, Page { limit: 10, offset: 0 }).await;
+/// }
+/// ```
+#[proc_macro_derive(
+    Read,
+    attributes(
+        crud_id,
+        crud_table,
+        crud_created_at,
+        crud_updated_at,
+        crud_version,
+        crud_belongs_to,
+        crud_many_to_many,
+        crud_json
+    )
+)]
+pub fn read_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = parse_struct_fields!(input);
+
+    let table = parse_table_attribute!(input);
+    let table_name = &table.name;
+    let (id_idents, id_types) = parse_id_attribute!(fields);
+    parse_json_fields!(input, fields);
+    let (id_type, id_bindings) = build_id_param(&id_types);
+
+    let read_query = build_query_as(
+        table.checked,
+        struct_name,
+        fields,
+        &format!(
+            "SELECT * FROM {} WHERE {}",
+            table_name,
+            build_id_condition(&id_idents),
+        ),
+        &id_bindings,
+    );
+    let read_one_query = build_query_as(
+        table.checked,
+        struct_name,
+        fields,
+        &format!(
+            "SELECT * FROM {} WHERE {}",
+            table_name,
+            build_id_condition(&id_idents),
+        ),
+        &id_bindings,
+    );
+    let read_all_query = build_query_as(
+        table.checked,
+        struct_name,
+        fields,
+        &format!("SELECT * FROM {}", table_name),
+        &[],
+    );
+
+    let entity_table = table_name.trim_matches('"');
+    let id_columns = id_idents.iter().map(|ident| ident.to_string());
+    let entity_id = match id_idents.as_slice() {
+        [id_ident] => quote! { self.#id_ident.clone() },
+        _ => quote! { (#(self.#id_idents.clone()),*) },
+    };
+
+    let entity_meta = build_entity_meta(struct_name, fields, table_name, "read", quote! { None });
+
+    let registration = build_entity_registration(
+        &input.generics,
+        quote! { <#struct_name as ::derive_crud::Entity>::META },
+    );
+    let read_repository = if input.generics.params.is_empty() {
+        quote! {
+            impl ::derive_crud::ReadRepository<#struct_name> for ::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite> {
+                async fn read_one(&self, id: #id_type) -> Result<#struct_name, ::derive_crud::CRUDError> {
+                    #struct_name::read_one(self, id).await
+                }
+
+                async fn read_all(&self) -> Result<::std::vec::Vec<#struct_name>, ::derive_crud::CRUDError> {
+                    #struct_name::read_all(self).await
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // The stream reports to the observer once it ends, and its span is
+    // entered while polling the query.
+    let entity = struct_name.to_string();
+    let tracing = cfg!(feature = "tracing");
+    let span = match tracing {
+        true => {
+            let name = format!("{struct_name}::read");
+            quote! {
+                let span = ::derive_crud::tracing::info_span!(
+                    #name,
+                    entity = #entity,
+                    table = #entity_table,
+                    operation = "read",
+                    id = ::derive_crud::tracing::field::debug(&id),
+                    rows_affected = ::derive_crud::tracing::field::Empty,
+                    error = ::derive_crud::tracing::field::Empty,
+                );
+            }
+        }
+        false => quote! {},
+    };
+    let instrument = match tracing {
+        true => quote! { .instrument(span.clone()) },
+        false => quote! {},
+    };
+    let use_instrument = match tracing {
+        true => quote! { use ::derive_crud::tracing::Instrument; },
+        false => quote! {},
+    };
+    let report = |rows: proc_macro2::TokenStream| {
+        let record = match tracing {
+            true => quote! { ::derive_crud::trace::record(&span, &result, #rows); },
+            false => quote! {},
+        };
+        quote! {
+            #record
+            ::derive_crud::observer::report(#entity, #entity_table, "read", started, &result, #rows);
+        }
+    };
+    let report_acquire = report(quote! { None });
+    let report_end = report(quote! { Some(rows) });
+    let read_body = quote! {
+        #span
+
+        Box::pin(::derive_crud::async_stream::stream! {
+            #use_instrument
+
+            let started = ::std::time::Instant::now();
+            let mut connection = match executor.acquire() #instrument .await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    let result: Result<(), _> = Err(::derive_crud::CRUDError::new(e.to_string()));
+                    #report_acquire
+                    if let Err(error) = result {
+                        yield Err(error);
+                    }
+                    return;
+                }
+            };
+
+            // Only the kind of the last error is reported, the errors
+            // themselves are yielded.
+            let mut rows: u64 = 0;
+            let mut result: Result<(), ::derive_crud::CRUDError> = Ok(());
+            let mut stream = #read_query.fetch(&mut *connection);
+            while let Some(item) = stream.next() #instrument .await {
+                match item {
+                    Ok(record) => {
+                        rows += 1;
+                        yield Ok(record);
+                    }
+                    Err(e) => {
+                        let error = ::derive_crud::CRUDError::new(e.to_string());
+                        result = Err(::derive_crud::CRUDError::with_kind(error.kind(), ""));
+                        yield Err(error);
+                    }
+                }
+            }
+            #report_end
+        })
+    };
+
+    let relation_loaders = parse_belongs_to_attributes!(fields)
+        .iter()
+        .map(|relation| {
+            build_belongs_to_loaders(
+                &input.vis,
+                struct_name,
+                &input.generics,
+                &table,
+                fields,
+                relation,
+            )
+        })
+        .collect::<Vec<_>>();
+    let many_to_many_methods = parse_many_to_many_attributes!(input)
+        .iter()
+        .map(|relation| build_many_to_many_methods(struct_name, table.checked, relation))
+        .collect::<Vec<_>>();
+
+    // Reading several keys at once binds them as a JSON array, which only
+    // supports single-column keys.
+    let read_many = match id_idents.as_slice() {
+        [id_ident] => {
+            let field_idents: Vec<_> = fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+                .collect();
+            let read_many_query = build_query_as(
+                table.checked,
+                struct_name,
+                fields,
+                &format!(
+                    "SELECT {} FROM {} WHERE {} IN (SELECT value FROM json_each(?))",
+                    build_output_columns(
+                        &field_idents,
+                        &optional_field_idents(fields),
+                        table.checked
+                    ),
+                    table_name,
+                    id_ident,
+                ),
+                &[quote! { ids }],
+            );
+            let read_many_body = build_observed(
+                struct_name,
+                &table.name,
+                "read_many",
+                Some(quote! { ids }),
+                Some(quote! { value.len() }),
+                quote! {
+                    let mut connection = executor
+                        .acquire()
+                        .await
+                        .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+                    let ids = ::derive_crud::serde_json::to_string(ids)
+                        .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+                    let items: ::std::vec::Vec<#struct_name> = #read_many_query
+                        .fetch_all(&mut *connection)
+                        .await
+                        .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+                    Ok(items)
+                },
+            );
+
+            quote! {
+                /// Reads every entry whose ID is one of the given IDs.
+                ///
+                /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
+                /// The field annotated with `#[crud_id]` is used as the identifier for the table.
+                pub async fn read_many<'c, A>(executor: A, ids: &[#id_type]) -> Result<::std::vec::Vec<Self>, ::derive_crud::CRUDError>
+                where
+                    A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+                {
+                    #read_many_body
+                }
+            }
+        }
+        _ => quote! {},
+    };
+
+    // The filter is a struct of its own, so generic structs are not listed.
+    let (page_filter, read_page) = match cfg!(feature = "page") && input.generics.params.is_empty()
+    {
+        true => build_read_page(&input.vis, struct_name, &table, fields, &id_idents),
+        false => (quote! {}, quote! {}),
+    };
+
+    let read_one_body = build_observed(
+        struct_name,
+        &table.name,
+        "read_one",
+        Some(quote! { id }),
+        Some(quote! { 1 }),
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let item = #read_one_query
+                .fetch_optional(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?
+                .ok_or_else(|| ::derive_crud::CRUDError::not_found("no row matched the id"))?;
+
+            Ok(item)
+        },
+    );
+
+    let read_all_body = build_observed(
+        struct_name,
+        &table.name,
+        "read_all",
+        None,
+        Some(quote! { value.len() }),
+        quote! {
+            let mut connection = executor
+                .acquire()
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            let items: ::std::vec::Vec<#struct_name> = #read_all_query
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+            Ok(items)
+        },
+    );
+
+    quote! {
+        impl #impl_generics ::derive_crud::Entity for #struct_name #ty_generics #where_clause {
+            type Id = #id_type;
+
+            const TABLE: &'static str = #entity_table;
+
+            const ID_COLUMNS: &'static [&'static str] = &[#(#id_columns),*];
+
+            const META: ::derive_crud::EntityMeta = #entity_meta;
+
+            fn id(&self) -> Self::Id {
+                #entity_id
+            }
+        }
+
+        #registration
+
+        #read_repository
+
+        #page_filter
+
+        #(#relation_loaders)*
+
+        #(#many_to_many_methods)*
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// Reads entries from the database by their ID.
+            ///
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
+            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
+            pub fn read<'c, A>(
+                executor: A,
+                id: #id_type
+            ) -> ::std::pin::Pin<Box<impl ::derive_crud::futures_core::stream::Stream<Item = Result<#struct_name, ::derive_crud::CRUDError>> + 'c>>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite> + 'c,
+            {
+                use ::derive_crud::futures_util::StreamExt;
+
+                #read_body
+            }
+
+            /// Reads a single entry from the database by its ID.
+            ///
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
+            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
+            pub async fn read_one<'c, A>(executor: A, id: #id_type) -> Result<Self, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #read_one_body
+            }
+
+            #read_many
+
+            /// Reads all entries from the database.
+            ///
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
+            pub async fn read_all<'c, A>(executor: A) -> Result<::std::vec::Vec<Self>, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #read_all_body
+            }
+
+            #read_page
+        }
+    }
+    .into()
+}
+
+/// Implements a CRUD function to update an item in the database table..
+///
+/// Generates a function that updates an entry in the database table with the
+/// current contents of the object. The new function will have the name
+/// `update`, and will take `&mut self` and no parameters. It fails with
+/// `CRUDErrorKind::NotFound` when no row has the key of the struct.
+///
+/// # Attributes
+///
+/// - `#[crud_id]`: Primary key for the database table. This attribute must be
+///   placed on at least one named field within the struct. Annotating several
+///   fields declares a composite key.
+/// - `#[crud_table("table_name")]`: Name of the database table that the struct
+///   is meant to represent. This attribute must be placed on the struct itself.
+///   With `#[crud_table("table_name", checked = false)]`, queries are not
+///   checked against a database at compile time, and rows are decoded through
+///   `sqlx::FromRow`, which the struct must derive.
+/// - `#[crud_created_at]` / `#[crud_updated_at]`: Optional audit timestamp
+///   columns. `created_at` is never written and `updated_at` is set to
+///   `CURRENT_TIMESTAMP`. When either is present, `update` refreshes the
+///   struct with the stored values.
+/// - `#[crud_version]`: Optional optimistic locking column. `update` only
+///   matches the row when the stored version equals the struct's version,
+///   increments it, and fails with `CRUDErrorKind::Conflict` otherwise.
+/// - `#[crud_hooks(before_update, after_update)]`: Calls the `BeforeUpdate`
+///   and `AfterUpdate` hooks before and after writing the row.
+/// - `#[crud_validate(...)]`: Checks the field before writing the row, with
+///   any of `length(min = 1, max = 255)`, `range(min = 0, max = 150)`,
+///   `email`, `regex = "^[a-z]+$"` and `custom = function`, where the function
+///   takes a reference to the value and returns `Result<(), String>`. Every
+///   failed check is listed by the returned `CRUDErrorKind::Validation` error,
+///   and `Option` fields are only checked when set.
+/// - `#[crud_audit("audit_table")]`: Writes every update to the audit table
+///   in the same transaction, and generates `update_audited`, see
+///   `#[derive(Create)]`.
+/// - `#[crud_outbox("outbox_table")]`: Publishes an event with the updated
+///   row to the outbox table in the same transaction.
+/// - `#[crud_json]`: Writes the field as JSON text, see `#[derive(Create)]`.
+///
+/// # Panics (Compile-time Errors)
+///
+/// This macro will cause a compile-time error if:
+/// - It is applied to an enum or union (only structs are supported).
+/// - The struct is not annotated with `#[crud_table("table_name")]`.
+/// - No field is annotated with `#[crud_id]`.
+/// - The struct does not accurately match the database schema for the table.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Update)]
+/// #[crud_table("users")]
+/// struct User {
+///    #[crud_id]
+///   id: i64,
+///   name: String,
+///   email: String,
+///   age: i32,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let _result = User {
+///       id: 1,
+///       name: "Debbie".to_string(),
+///       email: "debbie@hotmail.com",
+///       age: 47,
+///   }
+///   .update()
+///   .await;
+/// }
+/// ```
+#[proc_macro_derive(
+    Update,
+    attributes(
+        crud_id,
+        crud_table,
+        crud_created_at,
+        crud_updated_at,
+        crud_version,
+        crud_belongs_to,
+        crud_many_to_many,
+        crud_hooks,
+        crud_audit,
+        crud_outbox,
+        crud_validate,
+        crud_json
+    )
+)]
+pub fn update_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
 
     let struct_name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let fields = parse_struct_fields!(input);
 
-    let table_name = parse_table_attribute!(input);
-    let id_ident = parse_id_attribute!(fields);
-    let (column_idents, column_types) = parse_column_fields!(fields);
+    let table = parse_table_attribute!(input);
+    let (id_idents, _) = parse_id_attribute!(fields);
+    parse_json_fields!(input, fields);
+    let (column_idents, _) = parse_column_fields!(fields);
+    let created_at_ident = parse_optional_field_attribute!(fields, "crud_created_at");
+    let updated_at_ident = parse_optional_field_attribute!(fields, "crud_updated_at");
+    let version_ident = parse_optional_field_attribute!(fields, "crud_version");
+    let hooks = parse_hooks_attribute!(input);
+    let audit_table = parse_audit_attribute!(input);
+    let outbox_table = parse_outbox_attribute!(input);
+    let (acquire, commit) = build_acquire(
+        hooks.before_update
+            || hooks.after_update
+            || audit_table.is_some()
+            || outbox_table.is_some(),
+    );
+    let self_ids: Vec<_> = id_idents
+        .iter()
+        .map(|ident| quote! { self.#ident })
+        .collect();
+    // Updating a row which does not exist changes nothing to audit.
+    let (audit_before, audit) = match &audit_table {
+        Some(audit_table) => (
+            Some({
+                let before = build_row_snapshot(&table, fields, &id_idents, &self_ids);
+                quote! { let before = #before; }
+            }),
+            Some({
+                let record = build_audit_record(
+                    audit_table,
+                    &table,
+                    &id_idents,
+                    "update",
+                    quote! { before },
+                    build_row_snapshot(&table, fields, &id_idents, &self_ids),
+                );
+                quote! {
+                    if before.is_some() {
+                        #record
+                    }
+                }
+            }),
+        ),
+        None => (None, None),
+    };
+    let outbox = outbox_table.as_ref().map(|outbox_table| {
+        let payload = build_row_snapshot(&table, fields, &id_idents, &self_ids);
+        build_outbox_publish(outbox_table, &table, &id_idents, "update", payload)
+    });
+    let before_update = hooks.before_update.then(|| {
+        quote! { ::derive_crud::BeforeUpdate::before_update(&*self, &mut *connection).await?; }
+    });
+    let validation = match build_validation(fields, &column_idents, |ident| quote! { self.#ident })
+    {
+        Ok(validation) => validation,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let after_update = hooks.after_update.then(|| {
+        quote! { ::derive_crud::AfterUpdate::after_update(&*self, &mut *connection).await?; }
+    });
 
-    let fn_params = column_idents
+    let query = build_update_query(
+        &id_idents,
+        &table,
+        &column_idents,
+        created_at_ident,
+        updated_at_ident,
+        version_ident,
+        fields,
+    );
+    let arg_idents: Vec<_> = column_idents
         .iter()
-        .zip(column_types.iter())
-        .map(|(ident, ty)| {
-            quote! { #ident: #ty }
+        .chain(&id_idents)
+        .chain(&version_ident)
+        .copied()
+        .collect();
+    let (json_bindings, query_args) =
+        build_column_args(fields, &arg_idents, |ident| quote! { self.#ident });
+
+    let missing_row_error = match version_ident {
+        Some(_) => quote! {
+            ::derive_crud::CRUDError::conflict("the row was modified concurrently or no longer exists")
+        },
+        None => quote! {
+            ::derive_crud::CRUDError::not_found("no row matched the update")
+        },
+    };
+
+    let repository = if input.generics.params.is_empty() {
+        let mock_version = version_ident.map(|ident| {
+            quote! {
+                if self.#ident != stored.#ident {
+                    return Err(#missing_row_error);
+                }
+                self.#ident = stored.#ident + 1;
+            }
         });
+        let mock_created_at =
+            created_at_ident.map(|ident| quote! { self.#ident = stored.#ident.clone(); });
+        let mock = build_mock(quote! {
+            impl ::derive_crud::mock::MockUpdate for #struct_name {
+                #[allow(unused_variables)]
+                fn prepare_update(&mut self, stored: &Self) -> Result<(), ::derive_crud::CRUDError> {
+                    #mock_version
+                    #mock_created_at
 
-    let query = build_create_query(id_ident, &table_name, &column_idents);
+                    Ok(())
+                }
 
-    quote! {
-        impl #impl_generics #struct_name #ty_generics #where_clause {
-            /// Creates a new entry in the database.
+                fn missing_row() -> ::derive_crud::CRUDError {
+                    #missing_row_error
+                }
+            }
+        });
+
+        quote! {
+            impl ::derive_crud::UpdateRepository<#struct_name> for ::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite> {
+                async fn update(&self, item: &mut #struct_name) -> Result<(), ::derive_crud::CRUDError> {
+                    item.update(self).await
+                }
+            }
+
+            #mock
+        }
+    } else {
+        quote! {}
+    };
+
+    let registration = build_entity_registration(
+        &input.generics,
+        build_entity_meta(struct_name, fields, &table.name, "update", quote! { None }),
+    );
+
+    if created_at_ident.is_none() && updated_at_ident.is_none() && version_ident.is_none() {
+        let query = build_query(table.checked, &query, &query_args);
+
+        let execution = build_execute(quote! {
+            #query
+                .execute(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?
+        });
+        let update_body = build_observed(
+            struct_name,
+            &table.name,
+            "update",
+            Some(build_item_id(quote! { self }, &id_idents)),
+            None,
+            quote! {
+                #acquire
+
+                #before_update
+                #validation
+                #audit_before
+                #json_bindings
+                #execution
+                if rows_affected == Some(0) {
+                    return Err(#missing_row_error);
+                }
+                #audit
+                #outbox
+                #after_update
+                #commit
+
+                Ok(())
+            },
+        );
+        let update_fn = build_audited_fn(
+            audit_table.is_some(),
+            quote! {
+                /// Writes the updated fields of the struct to the database.
+                ///
+                /// The `#[crud_table("table_name")]` attribute specifies the database table to update.
+                /// The field annotated with `#[crud_id]` is used as the identifier for the table.
+            },
+            "update",
+            Some(quote! { &mut self, }),
+            &[],
+            &[],
+            quote! { () },
+            update_body,
+        );
+
+        return quote! {
+            #registration
+            #repository
+
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                #update_fn
+            }
+        }
+        .into();
+    }
+
+    let query = build_query_as(table.checked, struct_name, fields, &query, &query_args);
+
+    let update_body = build_observed(
+        struct_name,
+        &table.name,
+        "update",
+        Some(build_item_id(quote! { self }, &id_idents)),
+        Some(quote! { 1 }),
+        quote! {
+            #acquire
+
+            #before_update
+            #validation
+            #audit_before
+            #json_bindings
+            let item = #query
+                .fetch_optional(&mut *connection)
+                .await
+                .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?
+                .ok_or_else(|| #missing_row_error)?;
+
+            *self = item;
+            #audit
+            #outbox
+            #after_update
+            #commit
+
+            Ok(())
+        },
+    );
+    let update_fn = build_audited_fn(
+        audit_table.is_some(),
+        quote! {
+            /// Writes the updated fields of the struct to the database.
             ///
-            /// The `#[crud_table("table_name")]` attribute specifies the database table to insert into.
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to update.
             /// The field annotated with `#[crud_id]` is used as the identifier for the table.
-            pub async fn create(pool: &::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite>, #(#fn_params),*) -> Result<Self, ::derive_crud::CRUDError> {
-                let item = ::derive_crud::sqlx::query_as!(#struct_name, #query, #(#column_idents),*,)
-                    .fetch_one(pool)
-                    .await
-                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+            /// Fields annotated with `#[crud_created_at]`, `#[crud_updated_at]` or
+            /// `#[crud_version]` are refreshed with the values stored by the database.
+        },
+        "update",
+        Some(quote! { &mut self, }),
+        &[],
+        &[],
+        quote! { () },
+        update_body,
+    );
 
-                Ok(item)
-            }
+    quote! {
+        #registration
+        #repository
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #update_fn
         }
     }
     .into()
 }
 
-/// Implements a CRUD functions to read items from the database.
+/// Implements a CRUD function to delete an item from the database table.
 ///
-/// Generates functions that read one/multiple entries in the database table with a
-/// given ID, or to read all entries at once.
+/// Generates a function that deletes an entry in the database table with a
+/// provided ID. The new function will have the name `delete`, and will take
+/// the struct's ID field as a parameter. It fails with
+/// `CRUDErrorKind::NotFound` when no row has the ID, like `update`.
 ///
 ///
 /// # Attributes
 ///
 /// - `#[crud_id]`: Primary key for the database table. This attribute must be
-///   placed on a single named field within the struct.
+///   placed on at least one named field within the struct. Annotating several
+///   fields declares a composite key.
 /// - `#[crud_table("table_name")]`: Name of the database table that the struct
 ///   is meant to represent. This attribute must be placed on the struct itself.
+///   With `#[crud_table("table_name", checked = false)]`, queries are not
+///   checked against a database at compile time, and rows are decoded through
+///   `sqlx::FromRow`, which the struct must derive.
+/// - `#[crud_hooks(before_delete, after_delete)]`: Calls the `BeforeDelete`
+///   and `AfterDelete` hooks with the key before and after deleting the row.
+/// - `#[crud_audit("audit_table")]`: Writes every deletion to the audit table
+///   in the same transaction, and generates `delete_audited`, see
+///   `#[derive(Create)]`.
+/// - `#[crud_outbox("outbox_table")]`: Publishes an event with the deleted
+///   row to the outbox table in the same transaction.
 ///
 /// # Panics (Compile-time Errors)
 ///
 /// This macro will cause a compile-time error if:
 /// - It is applied to an enum or union (only structs are supported).
 /// - The struct is not annotated with `#[crud_table("table_name")]`.
-/// - A single field is not annotated with `#[crud_id]`.
+/// - No field is annotated with `#[crud_id]`.
 /// - The struct does not accurately match the database schema for the table.
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// #[derive(Read)]
+/// #[derive(Delete)]
 /// #[crud_table("users")]
 /// struct User {
 ///    #[crud_id]
@@ -156,146 +2502,379 @@ pub fn create_derive(input: TokenStream) -> TokenStream {
 ///
 /// #[tokio::main]
 /// async fn main() {
-///    let result = Test::read_one(1).await;
-///    let results = Test::read(1).await;
-///    let everything = Test::read_all().await;
+///   let _result = Test::delete(1).await;
 /// }
 /// ```
-#[proc_macro_derive(Read, attributes(crud_id, crud_table))]
-pub fn read_derive(input: TokenStream) -> TokenStream {
+#[proc_macro_derive(
+    Delete,
+    attributes(
+        crud_id,
+        crud_table,
+        crud_created_at,
+        crud_updated_at,
+        crud_version,
+        crud_belongs_to,
+        crud_many_to_many,
+        crud_hooks,
+        crud_audit,
+        crud_outbox
+    )
+)]
+pub fn delete_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
-
     let struct_name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
     let fields = parse_struct_fields!(input);
+    let (id_idents, id_types) = parse_id_attribute!(fields);
+    let (id_type, id_bindings) = build_id_param(&id_types);
 
-    let read_query = format!(
-        "SELECT * FROM {} WHERE {} = ?",
-        parse_table_attribute!(input),
-        parse_id_attribute!(fields),
+    let table = parse_table_attribute!(input);
+    let hooks = parse_hooks_attribute!(input);
+    let audit_table = parse_audit_attribute!(input);
+    let outbox_table = parse_outbox_attribute!(input);
+    let (acquire, commit) = build_acquire(
+        hooks.before_delete
+            || hooks.after_delete
+            || audit_table.is_some()
+            || outbox_table.is_some(),
     );
-    let read_one_query = format!(
-        "SELECT * FROM {} WHERE {} = ?",
-        parse_table_attribute!(input),
-        parse_id_attribute!(fields),
+    let audit_before = (audit_table.is_some() || outbox_table.is_some()).then(|| {
+        let before = build_row_snapshot(&table, fields, &id_idents, &id_bindings);
+        quote! { let before = #before; }
+    });
+    // Deleting a row which does not exist changes nothing to audit.
+    let audit = audit_table.as_ref().map(|audit_table| {
+        let record = build_audit_record(
+            audit_table,
+            &table,
+            &id_idents,
+            "delete",
+            match outbox_table {
+                Some(_) => quote! { before.clone() },
+                None => quote! { before },
+            },
+            quote! { None },
+        );
+        quote! {
+            if before.is_some() {
+                #record
+            }
+        }
+    });
+    let outbox = outbox_table.as_ref().map(|outbox_table| {
+        build_outbox_publish(
+            outbox_table,
+            &table,
+            &id_idents,
+            "delete",
+            quote! { before },
+        )
+    });
+    let before_delete = hooks.before_delete.then(|| {
+        quote! { <Self as ::derive_crud::BeforeDelete>::before_delete(&mut *connection, &id).await?; }
+    });
+    let after_delete = hooks.after_delete.then(|| {
+        quote! { <Self as ::derive_crud::AfterDelete>::after_delete(&mut *connection, &id).await?; }
+    });
+    let query = build_query(
+        table.checked,
+        &format!(
+            "DELETE FROM {} WHERE {}",
+            table.name,
+            build_id_condition(&id_idents),
+        ),
+        &id_bindings,
     );
-    let read_all_query = format!("SELECT * FROM {}", parse_table_attribute!(input),);
 
-    quote! {
-        impl #impl_generics #struct_name #ty_generics #where_clause {
-            /// Reads entries from the database by their ID.
-            ///
-            /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
-            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
-            pub fn read<'a>(
-                pool: &'a ::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite>,
-                id: i64
-            ) -> ::std::pin::Pin<Box<impl ::derive_crud::futures_core::stream::Stream<Item = Result<#struct_name, ::derive_crud::CRUDError>> + 'a>> {
-                use ::derive_crud::futures_util::StreamExt;
+    let repository = if input.generics.params.is_empty() {
+        let item_id = match id_idents.as_slice() {
+            [ident] => quote! { item.#ident.clone() },
+            idents => quote! { (#(item.#idents.clone()),*) },
+        };
 
-                Box::pin(::derive_crud::async_stream::stream! {
-                    let mut stream = ::derive_crud::sqlx::query_as!(#struct_name, #read_query, id).fetch(pool);
-                    while let Some(item) = stream.next().await {
-                        match item {
-                            Ok(record) => yield Ok(record),
-                            Err(e) => yield Err(::derive_crud::CRUDError::new(e.to_string())),
-                        }
-                    }
-                })
+        quote! {
+            impl ::derive_crud::DeleteRepository<#struct_name> for ::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite> {
+                async fn delete(&self, item: &#struct_name) -> Result<(), ::derive_crud::CRUDError> {
+                    #struct_name::delete(self, #item_id).await
+                }
             }
+        }
+    } else {
+        quote! {}
+    };
 
-            /// Reads a single entry from the database by its ID.
-            ///
-            /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
-            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
-            pub async fn read_one(pool: &::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite>, id: i64) -> Result<Self, ::derive_crud::CRUDError> {
-                let item = ::derive_crud::sqlx::query_as!(#struct_name, #read_one_query, id)
-                    .fetch_one(pool)
-                    .await
-                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+    let execution = build_execute(quote! {
+        #query
+            .execute(&mut *connection)
+            .await
+            .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?
+    });
+    let delete_body = build_observed(
+        struct_name,
+        &table.name,
+        "delete",
+        Some(quote! { id }),
+        None,
+        quote! {
+            #acquire
 
-                Ok(item)
+            #before_delete
+            #audit_before
+            #execution
+            if rows_affected == Some(0) {
+                return Err(::derive_crud::CRUDError::not_found("no row matched the id"));
             }
+            #audit
+            #outbox
+            #after_delete
+            #commit
 
-            /// Reads all entries from the database.
+            Ok(())
+        },
+    );
+    let delete_fn = build_audited_fn(
+        audit_table.is_some(),
+        quote! {
+            /// Deletes an entry from the database by its ID.
             ///
-            /// The `#[crud_table("table_name")]` attribute specifies the database table to read from.
-            pub async fn read_all(pool: &::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite>) -> Result<::std::vec::Vec<Self>, ::derive_crud::CRUDError> {
-                let items: ::std::vec::Vec<#struct_name> = ::derive_crud::sqlx::query_as!(#struct_name, #read_all_query)
-                    .fetch_all(pool)
-                    .await
-                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to delete from.
+            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
+        },
+        "delete",
+        None,
+        &[quote! { id: #id_type }],
+        &[quote! { id }],
+        quote! { () },
+        delete_body,
+    );
+    let registration = build_entity_registration(
+        &input.generics,
+        build_entity_meta(struct_name, fields, &table.name, "delete", quote! { None }),
+    );
 
-                Ok(items)
-            }
+    quote! {
+        #registration
+        #repository
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #delete_fn
         }
     }
     .into()
 }
 
-/// Implements a CRUD function to delete an item from the database table.
+/// Implements table definition and creation functions for the struct.
 ///
-/// Generates a function that deletes an entry in the database table with a
-/// provided ID. The new function will have the name `delete`, and will take
-/// the struct's ID field as a parameter.
+/// Generates the [`Schema`](../derive_crud/trait.Schema.html) trait, describing
+/// the table built from the struct fields, along with the functions
+/// `create_table` and `drop_table` which execute the matching `CREATE TABLE`
+/// and `DROP TABLE` statements. Non-generic structs are also registered for
+/// `derive_crud::migration::diff_registered` and the `derive-crud` binary.
 ///
+/// Column types are inferred from the field types (`INTEGER` for integers and
+/// `bool`, `REAL` for floats, `TEXT` for `String`, `BLOB` for `Vec<u8>`), and
+/// columns are `NOT NULL` unless the field is an `Option`.
 ///
 /// # Attributes
 ///
-/// - `#[crud_id]`: Primary key for the database table. This attribute must be
-///   placed on a single named field within the struct.
+/// - `#[crud_id]`: Primary key for the database table. A single `i64` key
+///   aliases the SQLite rowid, while several fields form a composite key.
 /// - `#[crud_table("table_name")]`: Name of the database table that the struct
 ///   is meant to represent. This attribute must be placed on the struct itself.
+/// - `#[crud_column(...)]`: Optional column constraints, any of `unique`,
+///   `default = "expression"`, `check = "expression"`,
+///   `references = "table(column)"` and `sql_type = "TYPE"`.
+/// - `#[crud_belongs_to(Target)]`: Adds a `REFERENCES` constraint to the key of
+///   `Target`, which must derive `Read`.
+/// - `#[crud_json]`: The field is stored as JSON in a `TEXT` column.
 ///
 /// # Panics (Compile-time Errors)
 ///
 /// This macro will cause a compile-time error if:
 /// - It is applied to an enum or union (only structs are supported).
 /// - The struct is not annotated with `#[crud_table("table_name")]`.
-/// - A single field is not annotated with `#[crud_id]`.
-/// - The struct does not accurately match the database schema for the table.
+/// - No field is annotated with `#[crud_id]`.
+/// - The SQLite type of a field cannot be inferred and is not given with
+///   `#[crud_column(sql_type = "TYPE")]`.
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// #[derive(Delete)]
+/// #[derive(Schema)]
 /// #[crud_table("users")]
 /// struct User {
-///    #[crud_id]
-///   id: i64,
-///   name: String,
-///   email: String,
-///   age: i32,
+///     #[crud_id]
+///     id: i64,
+///     #[crud_column(unique)]
+///     email: String,
+///     #[crud_column(check = "age >= 0")]
+///     age: i32,
+///     nickname: Option<String>,
 /// }
 ///
 /// #[tokio::main]
 /// async fn main() {
-///   let _result = Test::delete(1).await;
+///     let _result = User::create_table(&pool).await;
 /// }
 /// ```
-#[proc_macro_derive(Update, attributes(crud_id, crud_table))]
-pub fn update_derive(input: TokenStream) -> TokenStream {
+#[proc_macro_derive(
+    Schema,
+    attributes(
+        crud_id,
+        crud_table,
+        crud_column,
+        crud_created_at,
+        crud_updated_at,
+        crud_version,
+        crud_belongs_to,
+        crud_many_to_many,
+        crud_json
+    )
+)]
+pub fn schema_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
 
     let struct_name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let fields = parse_struct_fields!(input);
 
-    let table_name = parse_table_attribute!(input);
-    let id_ident = parse_id_attribute!(fields);
-    let (column_idents, _) = parse_column_fields!(fields);
+    let table_name = parse_table_attribute!(input).name;
+    let (id_idents, _) = parse_id_attribute!(fields);
+    let relations = parse_belongs_to_attributes!(fields);
+
+    let mut columns = Vec::new();
+    for field in fields.iter() {
+        let Some(field_ident) = field.ident.as_ref() else {
+            continue;
+        };
+
+        let options = match parse_column_options(field) {
+            Ok(options) => options,
+            Err(error) => return error.to_compile_error().into(),
+        };
+
+        let inferred_type = match is_json_field(field) {
+            true => Some("TEXT"),
+            false => sqlite_type_name(&field.ty),
+        };
+        let sql_type = match options.sql_type.as_deref().or(inferred_type) {
+            Some(sql_type) => sql_type.to_string(),
+            None => {
+                return syn::Error::new_spanned(
+                    &field.ty,
+                    "Cannot infer the SQLite type of this field, specify it with `#[crud_column(sql_type = \"TYPE\")]`",
+                )
+                .to_compile_error()
+                .into();
+            }
+        };
+
+        let name = field_ident.to_string();
+        let nullable = option_inner_type(&field.ty).is_some();
+        let unique = options.unique;
+        let default = match options.default {
+            Some(default) => quote! { Some(#default) },
+            None => quote! { None },
+        };
+        let check = match options.check {
+            Some(check) => quote! { Some(#check) },
+            None => quote! { None },
+        };
+        let relation = relations
+            .iter()
+            .find(|relation| relation.field_ident == field_ident);
+        let references = match (options.references, relation) {
+            (Some((table, column)), _) => quote! {
+                Some(::derive_crud::ForeignKey { table: #table, column: #column })
+            },
+            (None, Some(relation)) => {
+                let target = &relation.target;
+                quote! {
+                    Some(::derive_crud::ForeignKey {
+                        table: <#target as ::derive_crud::Entity>::TABLE,
+                        column: <#target as ::derive_crud::Entity>::ID_COLUMNS[0],
+                    })
+                }
+            }
+            (None, None) => quote! { None },
+        };
+
+        columns.push(quote! {
+            ::derive_crud::ColumnSchema {
+                name: #name,
+                sql_type: #sql_type,
+                nullable: #nullable,
+                unique: #unique,
+                default: #default,
+                check: #check,
+                references: #references,
+            }
+        });
+    }
+
+    let schema_table = table_name.trim_matches('"');
+    let primary_key = id_idents.iter().map(|ident| ident.to_string());
 
-    let query = build_update_query(id_ident, &table_name, &column_idents);
+    let registration = build_entity_registration(
+        &input.generics,
+        build_entity_meta(
+            struct_name,
+            fields,
+            &table_name,
+            "schema",
+            quote! { Some(&<#struct_name as ::derive_crud::Schema>::SCHEMA) },
+        ),
+    );
 
     quote! {
+        impl #impl_generics ::derive_crud::Schema for #struct_name #ty_generics #where_clause {
+            const SCHEMA: ::derive_crud::TableSchema = ::derive_crud::TableSchema {
+                name: #schema_table,
+                columns: &[#(#columns),*],
+                primary_key: &[#(#primary_key),*],
+            };
+        }
+
+        #registration
+
         impl #impl_generics #struct_name #ty_generics #where_clause {
-            /// Writes the updated fields of the struct to the database.
+            /// Creates the database table of the struct if it does not exist.
             ///
-            /// The `#[crud_table("table_name")]` attribute specifies the database table to update.
-            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
-            pub async fn update(&self, pool: &::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite>) -> Result<(), ::derive_crud::CRUDError> {
-                ::derive_crud::sqlx::query!(#query, self.#id_ident, #(self.#column_idents),*)
-                    .fetch_all(pool)
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to create.
+            pub async fn create_table<'c, A>(executor: A) -> Result<(), ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                let mut connection = executor
+                    .acquire()
+                    .await
+                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+                let sql = <Self as ::derive_crud::Schema>::SCHEMA.create_table_sql();
+                ::derive_crud::sqlx::query(&sql)
+                    .execute(&mut *connection)
+                    .await
+                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+                Ok(())
+            }
+
+            /// Drops the database table of the struct if it exists.
+            ///
+            /// The `#[crud_table("table_name")]` attribute specifies the database table to drop.
+            pub async fn drop_table<'c, A>(executor: A) -> Result<(), ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                let mut connection = executor
+                    .acquire()
+                    .await
+                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+                let sql = <Self as ::derive_crud::Schema>::SCHEMA.drop_table_sql();
+                ::derive_crud::sqlx::query(&sql)
+                    .execute(&mut *connection)
                     .await
                     .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
 
@@ -306,77 +2885,392 @@ pub fn update_derive(input: TokenStream) -> TokenStream {
     .into()
 }
 
-/// Implements a CRUD function to update an item in the database table..
+#[cfg(feature = "graphql")]
+fn to_camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = !camel.is_empty();
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// Implements a GraphQL API for a struct with `async-graphql`.
 ///
-/// Generates a function that updates an entry in the database table with the
-/// current contents of the object. The new function will have the name
-/// `update`, and will take no parameters.
+/// Generates an object type for the struct, with a field for every struct
+/// field, and for a `User` struct:
+///
+/// - `UserQuery`, with the query fields `user(id)`, reading an entry through
+///   the `UserLoader` dataloader, and `users(first, after, filter)`, a
+///   connection paging through `read_page` with a `UserFilterInput`.
+/// - `UserMutation`, with the mutations `createUser(input)`,
+///   `updateUser(id, input)`, setting the fields given in the input, and
+///   `deleteUser(id)`, returning the deleted entry.
+/// - `UserLoader`, loading entries by ID with `read_many`.
+///
+/// The resolvers read the pool and the `DataLoader<UserLoader>` from the
+/// data of the schema, and errors carry their kind in the `code` extension,
+/// see `derive_crud::graphql::error`. The struct must also derive `Create`,
+/// `Read`, `Update`, `Delete` and `Clone`, and have a single `#[crud_id]`.
+/// Only available with the `graphql` feature.
 ///
 /// # Attributes
 ///
-/// - `#[crud_id]`: Primary key for the database table. This attribute must be
-///   placed on a single named field within the struct.
-/// - `#[crud_table("table_name")]`: Name of the database table that the struct
-///   is meant to represent. This attribute must be placed on the struct itself.
+/// - `#[crud_graphql(plural = "name")]`: Optional name of the query field
+///   listing the entries, which defaults to the name of the struct in snake
+///   case followed by `s`.
 ///
 /// # Panics (Compile-time Errors)
 ///
 /// This macro will cause a compile-time error if:
-/// - It is applied to an enum or union (only structs are supported).
-/// - The struct is not annotated with `#[crud_table("table_name")]`.
-/// - A single field is not annotated with `#[crud_id]`.
-/// - The struct does not accurately match the database schema for the table.
+/// - It is applied to an enum, a union or a generic struct.
+/// - No field, or several fields, are annotated with `#[crud_id]`.
+/// - The `#[crud_graphql]` attribute has an unknown option.
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// #[derive(Update)]
+/// #[derive(Create, Read, Update, Delete, GraphQL, Clone)]
 /// #[crud_table("users")]
 /// struct User {
-///    #[crud_id]
-///   id: i64,
-///   name: String,
-///   email: String,
-///   age: i32,
+///     #[crud_id]
+///     id: i64,
+///     name: String,
 /// }
 ///
-/// #[tokio::main]
-/// async fn main() {
-///   let _result = User {
-///       id: 1,
-///       name: "Debbie".to_string(),
-///       email: "debbie@hotmail.com",
-///       age: 47,
-///   }
-///   .update()
-///   .await;
-/// }
+/// #[derive(MergedObject, Default)]
+/// struct Query(UserQuery);
+///
+/// #[derive(MergedObject, Default)]
+/// struct Mutation(UserMutation);
+///
+/// let schema = Schema::build(Query::default(), Mutation::default(), EmptySubscription)
+///     .data(DataLoader::new(UserLoader::new(pool.clone()), tokio::spawn))
+///     .data(pool)
+///     .finish();
 /// ```
-#[proc_macro_derive(Delete, attributes(crud_id, crud_table))]
-pub fn delete_derive(input: TokenStream) -> TokenStream {
+#[cfg(feature = "graphql")]
+#[proc_macro_derive(GraphQL, attributes(crud_id, crud_table, crud_graphql))]
+pub fn graphql_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
+
+    let vis = &input.vis;
     let struct_name = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = parse_struct_fields!(input);
+    let (id_idents, id_types) = parse_id_attribute!(fields);
+    let (column_idents, column_types) = parse_column_fields!(fields);
+    let id_generations = parse_id_generation!(fields);
+    let plural = parse_graphql_attribute!(input);
 
-    let query = format!(
-        "DELETE FROM {} WHERE {} = ?",
-        parse_table_attribute!(input),
-        parse_id_attribute!(parse_struct_fields!(input)),
-    );
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "`#[derive(GraphQL)]` is not supported on generic structs",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let ([id_ident], [id_type]) = (id_idents.as_slice(), id_types.as_slice()) else {
+        return syn::Error::new_spanned(
+            struct_name,
+            "`#[derive(GraphQL)]` requires a single `#[crud_id]` field",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let graphql = quote! { ::derive_crud::async_graphql };
+    let crate_path = "::derive_crud::async_graphql";
+    let docs = |attrs: &[syn::Attribute]| {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    // The resolvers are inherent methods of the struct, so they are prefixed
+    // to stay clear of its other methods.
+    let struct_docs = docs(&input.attrs);
+    let resolvers = fields.iter().filter_map(|field| {
+        let ident = field.ident.as_ref()?;
+        let ty = &field.ty;
+        let resolver = format_ident!("graphql_{}", ident);
+        let name = to_camel_case(&ident.to_string());
+        let field_docs = docs(&field.attrs);
+        Some(quote! {
+            #(#field_docs)*
+            #[graphql(name = #name)]
+            async fn #resolver(&self) -> &#ty {
+                &self.#ident
+            }
+        })
+    });
+
+    let snake_name = to_snake_case(&struct_name.to_string());
+    let one = format_ident!("{}", snake_name);
+    let many = match plural {
+        Some(plural) => format_ident!("{}", plural),
+        None => format_ident!("{}s", snake_name),
+    };
+    let create = format_ident!("create_{}", snake_name);
+    let update = format_ident!("update_{}", snake_name);
+    let delete = format_ident!("delete_{}", snake_name);
+    let query_name = format_ident!("{}Query", struct_name);
+    let mutation_name = format_ident!("{}Mutation", struct_name);
+    let loader_name = format_ident!("{}Loader", struct_name);
+    let filter_name = format_ident!("{}Filter", struct_name);
+    let filter_input_name = format_ident!("{}FilterInput", struct_name);
+    let create_input_name = format_ident!("Create{}Input", struct_name);
+    let update_input_name = format_ident!("Update{}Input", struct_name);
+
+    // The input has the fields of `{Struct}Filter`, which leaves out `#[crud_json]` fields.
+    let filter_idents: Vec<_> = fields
+        .iter()
+        .filter(|field| !is_json_field(field))
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+    let filter_fields = fields
+        .iter()
+        .filter(|field| !is_json_field(field))
+        .filter_map(|field| {
+            let ident = field.ident.as_ref()?;
+            let ty = option_inner_type(&field.ty).unwrap_or(&field.ty);
+            Some(quote! { pub #ident: Option<#ty> })
+        });
+
+    // Inputs take the same fields as `create`, including client-generated keys.
+    let mut create_idents = Vec::new();
+    let mut create_types = Vec::new();
+    if let [IdGeneration::Client] = id_generations.as_slice() {
+        create_idents.push(*id_ident);
+        create_types.push(*id_type);
+    }
+    create_idents.extend(column_idents.iter().copied());
+    create_types.extend(column_types.iter().copied());
+    let create_fields = create_idents.iter().zip(&create_types).map(|(ident, ty)| {
+        let field_docs = fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(*ident))
+            .map(|field| docs(&field.attrs))
+            .unwrap_or_default();
+        quote! {
+            #(#field_docs)*
+            pub #ident: #ty
+        }
+    });
+    let update_fields = column_idents.iter().zip(&column_types).map(|(ident, ty)| {
+        let ty = option_inner_type(ty).unwrap_or(ty);
+        quote! { pub #ident: Option<#ty> }
+    });
+    let update_assignments = column_idents.iter().zip(&column_types).map(|(ident, ty)| {
+        let value = match option_inner_type(ty) {
+            Some(_) => quote! { Some(value) },
+            None => quote! { value },
+        };
+        quote! {
+            if let Some(value) = input.#ident {
+                item.#ident = #value;
+            }
+        }
+    });
+
+    let create_input_doc = format!(" Fields of a new [`{struct_name}`].");
+    let update_input_doc =
+        format!(" Fields of a [`{struct_name}`] to update, leaving the others unchanged.");
+    let filter_input_doc = format!(" Filter of the [`{struct_name}`] entries listed by `{many}`.");
+    let query_doc = format!(" Query fields reading [`{struct_name}`] entries.");
+    let mutation_doc =
+        format!(" Mutations creating, updating and deleting [`{struct_name}`] entries.");
+    let loader_doc = format!(" Dataloader of [`{struct_name}`] entries by ID.");
+    let one_doc = format!(" Reads the `{struct_name}` with the given ID.");
+    let many_doc = format!(" Lists the `{struct_name}` entries matching `filter`, in ID order.");
+    let create_doc = format!(" Creates a `{struct_name}`.");
+    let update_doc = format!(" Updates the fields of the `{struct_name}` given in `input`.");
+    let delete_doc = format!(" Deletes a `{struct_name}`, returning it.");
 
     quote! {
-        impl #impl_generics #struct_name #ty_generics #where_clause {
-            /// Deletes an entry from the database by its ID.
-            ///
-            /// The `#[crud_table("table_name")]` attribute specifies the database table to delete from.
-            /// The field annotated with `#[crud_id]` is used as the identifier for the table.
-            pub async fn delete(pool: &::derive_crud::sqlx::Pool<::derive_crud::sqlx::Sqlite>, id: i64) -> Result<(), ::derive_crud::CRUDError> {
-                ::derive_crud::sqlx::query!(#query, id)
-                    .execute(pool)
+        #(#struct_docs)*
+        #[#graphql::Object(crate = #crate_path)]
+        impl #struct_name {
+            #(#resolvers)*
+        }
+
+        #[doc = #create_input_doc]
+        #[derive(#graphql::InputObject)]
+        #[graphql(crate = #crate_path)]
+        #vis struct #create_input_name {
+            #(#create_fields),*
+        }
+
+        #[doc = #update_input_doc]
+        #[derive(#graphql::InputObject)]
+        #[graphql(crate = #crate_path)]
+        #vis struct #update_input_name {
+            #(#update_fields),*
+        }
+
+        #[doc = #filter_input_doc]
+        #[derive(#graphql::InputObject, Default)]
+        #[graphql(crate = #crate_path)]
+        #vis struct #filter_input_name {
+            #(#filter_fields),*
+        }
+
+        impl From<#filter_input_name> for #filter_name {
+            fn from(input: #filter_input_name) -> Self {
+                #filter_name {
+                    #(#filter_idents: input.#filter_idents),*
+                }
+            }
+        }
+
+        #[doc = #loader_doc]
+        #vis struct #loader_name {
+            pool: ::derive_crud::sqlx::SqlitePool,
+        }
+
+        impl #loader_name {
+            /// Creates a loader reading from `pool`, to be wrapped in a `DataLoader`.
+            pub fn new(pool: ::derive_crud::sqlx::SqlitePool) -> Self {
+                #loader_name { pool }
+            }
+        }
+
+        impl #graphql::dataloader::Loader<#id_type> for #loader_name {
+            type Value = #struct_name;
+            type Error = #graphql::Error;
+
+            async fn load(
+                &self,
+                keys: &[#id_type],
+            ) -> Result<::std::collections::HashMap<#id_type, #struct_name>, Self::Error> {
+                let items = #struct_name::read_many(&self.pool, keys)
                     .await
-                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+                    .map_err(::derive_crud::graphql::error)?;
+                Ok(items
+                    .into_iter()
+                    .map(|item| (::derive_crud::Entity::id(&item), item))
+                    .collect())
+            }
+        }
 
-                Ok(())
+        #[doc = #query_doc]
+        #[derive(Default)]
+        #vis struct #query_name;
+
+        #[#graphql::Object(crate = #crate_path)]
+        impl #query_name {
+            #[doc = #one_doc]
+            async fn #one(
+                &self,
+                ctx: &#graphql::Context<'_>,
+                id: #id_type,
+            ) -> #graphql::Result<Option<#struct_name>> {
+                ctx.data::<#graphql::dataloader::DataLoader<#loader_name>>()?
+                    .load_one(id)
+                    .await
+            }
+
+            #[doc = #many_doc]
+            async fn #many(
+                &self,
+                ctx: &#graphql::Context<'_>,
+                first: Option<i32>,
+                after: Option<String>,
+                filter: Option<#filter_input_name>,
+            ) -> #graphql::Result<#graphql::connection::Connection<usize, #struct_name>> {
+                #graphql::connection::query(
+                    after,
+                    None,
+                    first,
+                    None,
+                    |after: Option<usize>, _, first, _| async move {
+                        let pool = ctx.data::<::derive_crud::sqlx::SqlitePool>()?;
+                        let filter: #filter_name = filter.unwrap_or_default().into();
+                        let offset = after.map_or(0, |after| after + 1);
+                        let limit = first.unwrap_or(50).min(1000);
+
+                        // One more row tells whether there is a next page.
+                        let page = ::derive_crud::Page {
+                            limit: limit as u32 + 1,
+                            offset: u32::try_from(offset).unwrap_or(u32::MAX),
+                        };
+                        let mut items = #struct_name::read_page(pool, &filter, page)
+                            .await
+                            .map_err(::derive_crud::graphql::error)?;
+                        let has_next_page = items.len() > limit;
+                        items.truncate(limit);
+
+                        let mut connection =
+                            #graphql::connection::Connection::new(offset > 0, has_next_page);
+                        connection.edges.extend(items.into_iter().enumerate().map(
+                            |(index, item)| #graphql::connection::Edge::new(offset + index, item),
+                        ));
+                        Ok::<_, #graphql::Error>(connection)
+                    },
+                )
+                .await
+            }
+        }
+
+        #[doc = #mutation_doc]
+        #[derive(Default)]
+        #vis struct #mutation_name;
+
+        #[#graphql::Object(crate = #crate_path)]
+        impl #mutation_name {
+            #[doc = #create_doc]
+            async fn #create(
+                &self,
+                ctx: &#graphql::Context<'_>,
+                input: #create_input_name,
+            ) -> #graphql::Result<#struct_name> {
+                let pool = ctx.data::<::derive_crud::sqlx::SqlitePool>()?;
+                #struct_name::create(pool, #(input.#create_idents),*)
+                    .await
+                    .map_err(::derive_crud::graphql::error)
+            }
+
+            #[doc = #update_doc]
+            async fn #update(
+                &self,
+                ctx: &#graphql::Context<'_>,
+                id: #id_type,
+                input: #update_input_name,
+            ) -> #graphql::Result<#struct_name> {
+                let pool = ctx.data::<::derive_crud::sqlx::SqlitePool>()?;
+                let mut item = #struct_name::read_one(pool, id)
+                    .await
+                    .map_err(::derive_crud::graphql::error)?;
+                #(#update_assignments)*
+                item.update(pool)
+                    .await
+                    .map_err(::derive_crud::graphql::error)?;
+                Ok(item)
+            }
+
+            #[doc = #delete_doc]
+            async fn #delete(
+                &self,
+                ctx: &#graphql::Context<'_>,
+                id: #id_type,
+            ) -> #graphql::Result<#struct_name> {
+                let pool = ctx.data::<::derive_crud::sqlx::SqlitePool>()?;
+                let item = #struct_name::read_one(pool, id)
+                    .await
+                    .map_err(::derive_crud::graphql::error)?;
+                ::derive_crud::DeleteRepository::delete(pool, &item)
+                    .await
+                    .map_err(::derive_crud::graphql::error)?;
+                Ok(item)
             }
         }
     }
@@ -1,42 +1,180 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use eyre::Result;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePool};
+use sqlx::{ConnectOptions, Connection, Sqlite, Transaction};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// Counter distinguishing the databases created by one test process.
+static DATABASE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// In-memory databases shared by the rollback tests, one per setup.
+static ROLLBACK_DATABASES: OnceLock<
+    Mutex<HashMap<TestDatabaseSetup, Arc<Mutex<SqliteConnection>>>>,
+> = OnceLock::new();
+
+/// Schema and data applied to a test database before the test runs.
+///
+/// Migrations run first, then the schema file, then the fixtures in the
+/// order they were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TestDatabaseSetup {
+    migrations: Option<PathBuf>,
+    schema: Option<PathBuf>,
+    fixtures: Vec<PathBuf>,
+}
+
+impl TestDatabaseSetup {
+    /// Runs the `sqlx migrate` migrations of the given directory.
+    pub fn migrations(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.migrations = Some(directory.into());
+        self
+    }
+
+    /// Executes the SQL statements of the given file.
+    pub fn schema(mut self, path: impl Into<PathBuf>) -> Self {
+        self.schema = Some(path.into());
+        self
+    }
+
+    /// Executes the SQL statements of the given file once the schema exists.
+    pub fn fixture(mut self, path: impl Into<PathBuf>) -> Self {
+        self.fixtures.push(path.into());
+        self
+    }
+
+    async fn apply(&self, connection: &mut SqliteConnection) -> Result<()> {
+        if let Some(directory) = &self.migrations {
+            Migrator::new(directory.as_path())
+                .await?
+                .run(&mut *connection)
+                .await?;
+        }
+
+        for path in self.schema.iter().chain(&self.fixtures) {
+            let sql = std::fs::read_to_string(path)
+                .map_err(|e| eyre::eyre!("Failed to read {}: {e}", path.display()))?;
+            sqlx::raw_sql(&sql).execute(&mut *connection).await?;
+        }
 
+        Ok(())
+    }
+}
+
+/// Temporary SQLite database owned by a single test.
+///
+/// Every manager creates its own uniquely named file in the system temporary
+/// directory, so tests using it can run in parallel. The file is deleted when
+/// the manager is dropped.
 #[derive(Debug)]
 pub struct TestDatabaseManager {
-    database_path: String,
+    database_path: PathBuf,
+    pool: SqlitePool,
 }
 
 impl TestDatabaseManager {
+    /// Creates an empty test database.
     pub async fn new() -> Result<TestDatabaseManager> {
-        let database_url = std::env::var("DATABASE_URL")?;
-        let database_path = match database_url.strip_prefix("sqlite://") {
-            Some(path) => path.to_string(),
-            None => {
-                return Err(eyre::eyre!("DATABASE_URL must start with 'sqlite://'"));
-            }
+        TestDatabaseManager::with_setup(&TestDatabaseSetup::default()).await
+    }
+
+    /// Creates a test database and applies the given setup to it.
+    pub async fn with_setup(setup: &TestDatabaseSetup) -> Result<TestDatabaseManager> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+        let database_path = std::env::temp_dir().join(format!(
+            "crud-test-{}-{}-{}.db",
+            std::process::id(),
+            DATABASE_COUNTER.fetch_add(1, Ordering::Relaxed),
+            nanos,
+        ));
+
+        let options = SqliteConnectOptions::new()
+            .filename(&database_path)
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await?;
+        let manager = TestDatabaseManager {
+            database_path,
+            pool,
         };
+        setup.apply(&mut *manager.pool.acquire().await?).await?;
 
-        if std::path::Path::new(&database_path).exists() {
-            std::fs::remove_file(&database_path)?;
-        }
-        std::fs::File::create(&database_path)?;
+        Ok(manager)
+    }
 
-        let pool = sqlx::sqlite::SqlitePool::connect(&database_url);
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS test_table (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
-        )
-        .execute(&pool.await?)
-        .await?;
+    /// Returns the pool connected to the test database.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
 
-        Ok(TestDatabaseManager {
-            database_path: database_path.to_string(),
-        })
+    /// Returns the `sqlite://` URL of the test database.
+    pub fn database_url(&self) -> String {
+        format!("sqlite://{}", self.database_path.display())
     }
 }
 
 impl Drop for TestDatabaseManager {
     fn drop(&mut self) {
-        if std::path::Path::new(&self.database_path).exists() {
-            std::fs::remove_file(&self.database_path).expect("Failed to delete test database file");
+        // Open connections do not prevent unlinking the file on Unix, and the
+        // journal files are only present while the database is in use.
+        for suffix in ["", "-wal", "-shm", "-journal"] {
+            let mut path = self.database_path.clone().into_os_string();
+            path.push(suffix);
+            let _ = std::fs::remove_file(path);
         }
     }
 }
+
+/// Shared in-memory SQLite database whose changes are rolled back after each
+/// test.
+///
+/// The database is created and set up the first time a setup is used, then
+/// reused by every later test with the same setup, which skips recreating the
+/// schema. Tests sharing a database hold it one at a time.
+#[derive(Debug)]
+pub struct RollbackTestDatabase {
+    connection: OwnedMutexGuard<SqliteConnection>,
+}
+
+impl RollbackTestDatabase {
+    /// Waits until the database of the given setup is free and takes it,
+    /// creating it first if no test used the setup yet.
+    pub async fn acquire(setup: &TestDatabaseSetup) -> Result<RollbackTestDatabase> {
+        let database = {
+            let mut databases = ROLLBACK_DATABASES
+                .get_or_init(Default::default)
+                .lock()
+                .await;
+            match databases.get(setup) {
+                Some(database) => database.clone(),
+                None => {
+                    let mut connection = SqliteConnectOptions::new()
+                        .in_memory(true)
+                        .connect()
+                        .await?;
+                    setup.apply(&mut connection).await?;
+
+                    let database = Arc::new(Mutex::new(connection));
+                    databases.insert(setup.clone(), database.clone());
+                    database
+                }
+            }
+        };
+
+        Ok(RollbackTestDatabase {
+            connection: database.lock_owned().await,
+        })
+    }
+
+    /// Starts the transaction of the test.
+    ///
+    /// The transaction is rolled back when dropped without being committed,
+    /// including when the test panics.
+    pub async fn begin(&mut self) -> Result<Transaction<'_, Sqlite>> {
+        Ok(self.connection.begin().await?)
+    }
+}
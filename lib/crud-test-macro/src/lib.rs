@@ -1,19 +1,349 @@
+use crud_macro_core::{
+    parse_column_fields, parse_factory_value, parse_id_attribute, parse_id_generation,
+    parse_struct_fields, FactoryValue, IdGeneration,
+};
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, FnArg, ItemFn, LitStr, Token};
 
+/// Runs an async test against its own temporary SQLite database.
+///
+/// The test may take the pool connected to the database as its only
+/// parameter, e.g. `async fn creates_users(pool: sqlx::SqlitePool)`. The
+/// database is deleted once the test returns, and tests run in parallel.
+///
+/// # Arguments
+///
+/// Paths are relative to the manifest directory of the crate under test.
+///
+/// - `migrations = "./migrations"`: Runs the `sqlx migrate` migrations of the
+///   directory.
+/// - `schema = "schema.sql"`: Executes the statements of the file.
+/// - `fixtures("users.sql", "orders.sql")`: Executes the statements of each
+///   file, in order, once the migrations and schema are applied.
+/// - `rollback`: Runs the test inside a transaction of a shared in-memory
+///   database instead, see below.
+///
+/// # Rollback mode
+///
+/// With `rollback`, the database is set up once and shared by every rollback
+/// test with the same arguments. The test takes a
+/// `&mut sqlx::Transaction<'_, sqlx::Sqlite>` instead of a pool, and the
+/// transaction is rolled back once the test returns or panics. Tests sharing
+/// a database run one at a time.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[database_test(schema = "tests/schema.sql", fixtures("tests/users.sql"))]
+/// fn reads_users(pool: sqlx::SqlitePool) {
+///     assert_eq!(User::read_all(&pool).await.unwrap().len(), 2);
+/// }
+///
+/// #[database_test(rollback, schema = "tests/schema.sql")]
+/// fn creates_users(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) {
+///     User::create(&mut *tx, "Debbie".to_string()).await.unwrap();
+///     assert_eq!(User::read_all(&mut *tx).await.unwrap().len(), 1);
+/// }
+/// ```
 #[proc_macro_attribute]
-pub fn database_test(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn database_test(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemFn);
+    let attrs = &input.attrs;
     let name = &input.sig.ident;
     let block = &input.block;
 
+    let mut setup = Vec::new();
+    let mut rollback = false;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("rollback") {
+            rollback = true;
+        } else if meta.path.is_ident("migrations") {
+            let path: LitStr = meta.value()?.parse()?;
+            setup.push(quote! { .migrations(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(#path)) });
+        } else if meta.path.is_ident("schema") {
+            let path: LitStr = meta.value()?.parse()?;
+            setup.push(
+                quote! { .schema(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(#path)) },
+            );
+        } else if meta.path.is_ident("fixtures") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            for path in content.parse_terminated(<LitStr as syn::parse::Parse>::parse, Token![,])? {
+                setup.push(quote! { .fixture(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(#path)) });
+            }
+        } else {
+            return Err(meta.error(
+                "Unknown `#[database_test]` argument, expected `migrations`, `schema`, `fixtures` or `rollback`",
+            ));
+        }
+        Ok(())
+    });
+    parse_macro_input!(attr with parser);
+
+    let binding = match input.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [] => None,
+        [FnArg::Typed(parameter)] => Some((&parameter.pat, &parameter.ty)),
+        _ if rollback => {
+            return syn::Error::new_spanned(
+                &input.sig.inputs,
+                "A rollback database test takes at most one parameter, the `&mut sqlx::Transaction` of the test",
+            )
+            .to_compile_error()
+            .into();
+        }
+        _ => {
+            return syn::Error::new_spanned(
+                &input.sig.inputs,
+                "A database test takes at most one parameter, the `sqlx::SqlitePool` of the test database",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    if rollback {
+        let transaction_binding =
+            binding.map(|(pat, ty)| quote! { let #pat: #ty = &mut _transaction; });
+
+        return quote! {
+            #(#attrs)*
+            #[tokio::test]
+            async fn #name() {
+                let mut _rollback_database = crud_test_core::RollbackTestDatabase::acquire(
+                    &crud_test_core::TestDatabaseSetup::default() #(#setup)*
+                )
+                .await
+                .expect("Failed to initialize test database");
+                let mut _transaction = _rollback_database
+                    .begin()
+                    .await
+                    .expect("Failed to begin test transaction");
+                {
+                    #transaction_binding
+                    #block
+                }
+                _transaction
+                    .rollback()
+                    .await
+                    .expect("Failed to roll back test transaction");
+            }
+        }
+        .into();
+    }
+
+    let pool_binding =
+        binding.map(|(pat, ty)| quote! { let #pat: #ty = _db_manager.pool().clone(); });
+
     quote! {
+        #(#attrs)*
         #[tokio::test]
-        #[serial_test::serial]
         async fn #name() {
-            let _db_manager = crud_test_core::TestDatabaseManager::new().await.expect("Failed to initialize test database");
+            let _db_manager = crud_test_core::TestDatabaseManager::with_setup(
+                &crud_test_core::TestDatabaseSetup::default() #(#setup)*
+            )
+            .await
+            .expect("Failed to initialize test database");
+            #pool_binding
             #block
         }
-    }.into()
+    }
+    .into()
+}
+
+/// Returns the value of a field without `#[factory]`, based on its type.
+///
+/// Text is made unique with the sequence number `n`, numbers are set to it,
+/// `Option`s are `None` and other types use their `Default`.
+fn inferred_value(ident: &syn::Ident, ty: &syn::Type) -> proc_macro2::TokenStream {
+    let type_name = match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    };
+
+    match type_name.as_deref() {
+        Some("Option") => quote! { None },
+        Some("String") => {
+            let prefix = ident.to_string();
+            quote! { format!("{}_{}", #prefix, n) }
+        }
+        Some(
+            "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" | "f32"
+            | "f64",
+        ) => quote! { n as #ty },
+        _ => quote! { ::std::default::Default::default() },
+    }
+}
+
+/// Generates a builder inserting rows of the struct for tests.
+///
+/// The builder is named after the struct with a `Factory` suffix, has the
+/// visibility of the struct and is returned by `Struct::factory()`. It has a
+/// setter for every parameter of the derived `create` function, and fills the
+/// parameters that are not set:
+///
+/// - `String` fields with `<field>_<n>`, and number fields with `n`, where `n`
+///   is a sequence number incremented with every row built by the factory.
+/// - `Option` fields with `None`, and other fields with their `Default`.
+///
+/// `insert(executor)` creates one row and `insert_many(executor, count)` creates
+/// several, both through `create`. Values passed to the setters are cloned for
+/// every row.
+///
+/// # Attributes
+///
+/// - `#[factory(sequence = "user_{n}@example.com")]`: Formats the value with the
+///   sequence number.
+/// - `#[factory(default = "expression")]`: Evaluates the expression, in which
+///   the sequence number is bound to `n`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Create, Factory)]
+/// #[crud_table("users")]
+/// struct User {
+///     #[crud_id]
+///     id: i64,
+///     #[factory(sequence = "user_{n}@example.com")]
+///     email: String,
+///     #[factory(default = "30")]
+///     age: i32,
+///     name: String,
+/// }
+///
+/// let debbie = User::factory().name("Debbie").insert(&pool).await?;
+/// let users = User::factory().age(47).insert_many(&pool, 10).await?;
+/// ```
+#[proc_macro_derive(Factory, attributes(factory))]
+pub fn factory_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &input.generics,
+            "Factory cannot be derived for generic structs",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let fields = parse_struct_fields!(input);
+    let factory_name = format_ident!("{}Factory", struct_name);
+
+    // The parameters of `create`, in the same order.
+    let (id_idents, id_types) = parse_id_attribute!(fields);
+    let id_generations = parse_id_generation!(fields);
+    let (column_idents, column_types) = parse_column_fields!(fields);
+    let mut param_idents = Vec::new();
+    let mut param_types = Vec::new();
+    for ((ident, ty), generation) in id_idents.iter().zip(&id_types).zip(&id_generations) {
+        if let IdGeneration::Client = generation {
+            param_idents.push(*ident);
+            param_types.push(*ty);
+        }
+    }
+    param_idents.extend(column_idents.iter().copied());
+    param_types.extend(column_types.iter().copied());
+
+    let mut values = Vec::new();
+    for ident in &param_idents {
+        let field = fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(*ident))
+            .unwrap();
+        let value = match parse_factory_value(field) {
+            Ok(FactoryValue::Inferred) => inferred_value(ident, &field.ty),
+            Ok(FactoryValue::Default(expr)) => quote! { #expr },
+            Ok(FactoryValue::Sequence(format)) => {
+                quote! { ::std::convert::Into::into(format!(#format, n = n)) }
+            }
+            Err(error) => return error.to_compile_error().into(),
+        };
+        values.push(value);
+    }
+
+    let setter_docs = param_idents
+        .iter()
+        .map(|ident| format!("Sets `{ident}` for every row instead of its generated value."));
+
+    quote! {
+        /// Builder inserting rows for tests, generated by `#[derive(Factory)]`.
+        #vis struct #factory_name {
+            #(#param_idents: Option<#param_types>,)*
+        }
+
+        impl ::std::default::Default for #factory_name {
+            fn default() -> Self {
+                #factory_name {
+                    #(#param_idents: None,)*
+                }
+            }
+        }
+
+        impl #struct_name {
+            /// Returns a builder inserting rows with generated values.
+            #vis fn factory() -> #factory_name {
+                #factory_name::default()
+            }
+        }
+
+        impl #factory_name {
+            #(
+                #[doc = #setter_docs]
+                #vis fn #param_idents(mut self, value: impl Into<#param_types>) -> Self {
+                    self.#param_idents = Some(value.into());
+                    self
+                }
+            )*
+
+            /// Returns the next sequence number of the factory, starting at `1`.
+            fn next_sequence() -> u64 {
+                static SEQUENCE: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(1);
+                SEQUENCE.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed)
+            }
+
+            /// Creates one row through the derived `create` function.
+            #vis async fn insert<'c, A>(&self, executor: A) -> Result<#struct_name, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                #[allow(unused_variables)]
+                let n = Self::next_sequence();
+                #(
+                    let #param_idents: #param_types = match &self.#param_idents {
+                        Some(value) => value.clone(),
+                        None => #values,
+                    };
+                )*
+
+                #struct_name::create(executor, #(#param_idents),*).await
+            }
+
+            /// Creates `count` rows through the derived `create` function, using
+            /// a single connection.
+            #vis async fn insert_many<'c, A>(&self, executor: A, count: usize) -> Result<::std::vec::Vec<#struct_name>, ::derive_crud::CRUDError>
+            where
+                A: ::derive_crud::sqlx::Acquire<'c, Database = ::derive_crud::sqlx::Sqlite>,
+            {
+                let mut connection = executor
+                    .acquire()
+                    .await
+                    .map_err(|e| ::derive_crud::CRUDError::new(e.to_string()))?;
+
+                let mut items = ::std::vec::Vec::with_capacity(count);
+                for _ in 0..count {
+                    items.push(self.insert(&mut *connection).await?);
+                }
+
+                Ok(items)
+            }
+        }
+    }
+    .into()
 }
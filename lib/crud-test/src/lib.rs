@@ -1,2 +1,2 @@
-pub use crud_test_core::TestDatabaseManager;
-pub use crud_test_macro::database_test;
+pub use crud_test_core::{RollbackTestDatabase, TestDatabaseManager, TestDatabaseSetup};
+pub use crud_test_macro::{database_test, Factory};
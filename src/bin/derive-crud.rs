@@ -0,0 +1,3 @@
+fn main() {
+    derive_crud::cli::main();
+}
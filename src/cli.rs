@@ -0,0 +1,128 @@
+//! Command line interface of the `derive-crud` binary.
+//!
+//! The binary only knows the structs linked into it, and the `derive-crud`
+//! binary of this crate links none, so it refuses to run. Projects build the
+//! CLI in their own crate instead, with the `cli` feature enabled and a
+//! `src/bin/derive-crud.rs` which links the structs deriving `Schema` and
+//! calls [`main`]:
+//!
+//! ```rust,ignore
+//! use my_app as _;
+//!
+//! fn main() {
+//!     derive_crud::cli::main();
+//! }
+//! ```
+
+use std::path::PathBuf;
+
+use crate::migration;
+
+const USAGE: &str = "\
+Usage: derive-crud migrate [OPTIONS]
+
+Compares the structs deriving `Schema` to a SQLite database and writes a
+migration file compatible with `sqlx migrate run`.
+
+Options:
+  --database-url <URL>   Database to compare against [env: DATABASE_URL]
+  --source <DIR>         Directory of the migrations [default: migrations]
+  --name <DESCRIPTION>   Description of the migration [default: derive_crud]
+  --dry-run              Print the migration instead of writing it
+  -h, --help             Print this message";
+
+const NO_SCHEMAS: &str = "\
+No struct deriving `Schema` is linked into this binary, so there is nothing to
+compare the database to. Build the CLI in the crate defining the structs, with
+a `src/bin/derive-crud.rs` calling `derive_crud::cli::main()`.";
+
+/// Options of the `migrate` command.
+#[derive(Debug)]
+struct MigrateOptions {
+    database_url: String,
+    source: PathBuf,
+    name: String,
+    dry_run: bool,
+}
+
+impl MigrateOptions {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<MigrateOptions, String> {
+        let mut database_url = std::env::var("DATABASE_URL").ok();
+        let mut source = PathBuf::from("migrations");
+        let mut name = "derive_crud".to_string();
+        let mut dry_run = false;
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for `{arg}`"));
+            match arg.as_str() {
+                "--database-url" => database_url = Some(value()?),
+                "--source" => source = PathBuf::from(value()?),
+                "--name" => name = value()?,
+                "--dry-run" => dry_run = true,
+                _ => return Err(format!("Unexpected argument `{arg}`")),
+            }
+        }
+
+        Ok(MigrateOptions {
+            database_url: database_url
+                .ok_or("Missing `--database-url` and `DATABASE_URL` is not set")?,
+            source,
+            name,
+            dry_run,
+        })
+    }
+}
+
+/// Runs the command line interface with the given arguments, excluding the
+/// binary name.
+pub async fn run(args: impl IntoIterator<Item = String>) -> Result<(), String> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("migrate") => {}
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+        Some(command) => return Err(format!("Unknown command `{command}`\n\n{USAGE}")),
+        None => return Err(USAGE.to_string()),
+    }
+
+    let options = MigrateOptions::parse(args)?;
+    if crate::registered_schemas().next().is_none() {
+        return Err(NO_SCHEMAS.to_string());
+    }
+
+    let pool = sqlx::SqlitePool::connect(&options.database_url)
+        .await
+        .map_err(|e| e.to_string())?;
+    let migration = migration::diff_registered(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if migration.is_empty() {
+        println!("Database is up to date");
+    } else if options.dry_run {
+        print!("{}", migration.to_sql());
+    } else {
+        let path = migration
+            .write(&options.source, &options.name)
+            .map_err(|e| e.to_string())?;
+        println!("Created migration {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Runs the command line interface with the process arguments, exiting with a
+/// non-zero status on failure.
+pub fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to start the Tokio runtime");
+
+    if let Err(error) = runtime.block_on(run(std::env::args().skip(1))) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -3,13 +3,309 @@
 //! `derive-crud` is a set of derive macros which automatically implement CRUD
 //! access functions. Under the hood, it uses the SQLx crate's `query!` macro
 //! to generate SQL queries at compile time, ensuring type safety and performance.
+//!
+//! ## Transactions
+//!
+//! The generated functions accept any SQLx executor which can acquire a
+//! connection: a `&Pool<Sqlite>`, a `&mut SqliteConnection` or a
+//! `&mut Transaction<'_, Sqlite>`. Several calls can therefore share a
+//! transaction:
+//!
+//! ```rust,ignore
+//! let mut tx = pool.begin().await?;
+//! let user = User::create(&mut *tx, "Debbie".to_string()).await?;
+//! Order::create(&mut *tx, user.id, "Book".to_string()).await?;
+//! tx.commit().await?;
+//! ```
+//!
+//! ## Repositories and mocks
+//!
+//! Each derive also implements a repository trait for `Pool<Sqlite>`:
+//! [`CreateRepository`], [`ReadRepository`], [`UpdateRepository`] and
+//! [`DeleteRepository`], combined in [`Repository`]. With the `mock` feature,
+//! `mock::MockRepository` implements the same traits in memory, so code
+//! written against them can be tested without a database:
+//!
+//! ```rust,ignore
+//! async fn rename<R: Repository<User>>(users: &R, id: i64, name: &str) -> Result<User, CRUDError> {
+//!     let mut user = users.read_one(id).await?;
+//!     user.name = name.to_string();
+//!     users.update(&mut user).await?;
+//!     Ok(user)
+//! }
+//!
+//! let users = MockRepository::<User>::new();
+//! let debbie = users.create(User { id: 0, name: "Debbie".to_string() }).await?;
+//! rename(&users, debbie.id, "Deborah").await?;
+//! ```
+//!
+//! The mock requires the struct to implement `Clone`. Repository traits are
+//! only implemented for non-generic structs.
+//!
+//! ## Lifecycle hooks
+//!
+//! `#[crud_hooks(before_create, after_create, before_update, after_update,
+//! before_delete, after_delete)]` makes the derived functions call the
+//! matching hook trait, such as [`BeforeCreate`], which the struct must
+//! implement. A hooked operation runs in a transaction, or a savepoint when
+//! the executor already is one, and hooks receive its connection, so their
+//! queries are part of it and an error returned by a hook rolls the operation
+//! back:
+//!
+//! ```rust,ignore
+//! #[derive(Create)]
+//! #[crud_table("users")]
+//! #[crud_hooks(before_create)]
+//! struct User {
+//!     #[crud_id]
+//!     id: i64,
+//!     email: String,
+//! }
+//!
+//! impl BeforeCreate for User {
+//!     async fn before_create(_: &mut SqliteConnection, values: &mut NewUser) -> Result<(), CRUDError> {
+//!         values.email = values.email.to_lowercase();
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! `before_create` receives the parameters of `create` in a generated
+//! `New<Struct>`, and the delete hooks the key of the row, which requires the
+//! struct to also derive `Read`. Hooks are not supported on generic structs,
+//! and are not run by `mock::MockRepository`.
+//!
+//! ## Validation
+//!
+//! `#[crud_validate(...)]` on a field makes `create` and `update` check it
+//! once the before-hooks ran, and return a [`CRUDErrorKind::Validation`] error
+//! listing every failed [`FieldError`] without executing any SQL:
+//!
+//! ```rust,ignore
+//! #[derive(Create, Update)]
+//! #[crud_table("users")]
+//! struct User {
+//!     #[crud_id]
+//!     id: i64,
+//!     #[crud_validate(email, length(max = 255))]
+//!     email: String,
+//!     #[crud_validate(range(min = 0, max = 150))]
+//!     age: i32,
+//! }
+//! ```
+//!
+//! ## JSON fields
+//!
+//! `#[crud_json]` stores a field which SQLite cannot hold, such as a list or a
+//! nested struct, as JSON in a TEXT column. The field is serialized with serde
+//! by `create` and `update`, and deserialized from the rows read back:
+//!
+//! ```rust,ignore
+//! #[derive(Create, Read, Update)]
+//! #[crud_table("profiles")]
+//! struct Profile {
+//!     #[crud_id]
+//!     id: i64,
+//!     #[crud_json]
+//!     tags: Vec<String>,
+//!     #[crud_json]
+//!     settings: Option<Settings>,
+//! }
+//! ```
+//!
+//! The queries still check at compile time that the column holds text, and
+//! a `NULL` column is decoded as `None` for `Option` fields. The audit log and
+//! outbox nest the field as JSON rather than as its text.
+//!
+//! ## Audit log
+//!
+//! `#[crud_audit("audit_log")]` makes `create`, `update` and `delete` write a
+//! row to the audit table, in a transaction (or a savepoint of the
+//! transaction of the executor) shared with the change. The derives also
+//! generate `create_audited`, `update_audited` and `delete_audited`, which
+//! take an [`audit::AuditContext`] naming the actor after the executor:
+//!
+//! ```rust,ignore
+//! User::create_audited(&pool, &AuditContext::actor("alice"), "Debbie".to_string()).await?;
+//! ```
+//!
+//! The audit table must have the following columns, where `before` and
+//! `after` are JSON objects of the row, restricted to the changed fields for
+//! updates:
+//!
+//! ```sql
+//! CREATE TABLE audit_log (
+//!     id INTEGER PRIMARY KEY,
+//!     table_name TEXT NOT NULL,
+//!     entity_id TEXT NOT NULL,
+//!     operation TEXT NOT NULL,
+//!     before TEXT,
+//!     after TEXT,
+//!     actor TEXT,
+//!     created_at TEXT NOT NULL
+//! );
+//! ```
+//!
+//! ## Outbox
+//!
+//! `#[crud_outbox("outbox")]` makes `create`, `update` and `delete` insert an
+//! event with the changed row into the outbox table, in the transaction of
+//! the change, so that an event is published if and only if the change is
+//! committed. With the `outbox-consumer` feature, an `outbox::OutboxConsumer`
+//! streams the events in order, and new consumers read them again until they
+//! are acknowledged:
+//!
+//! ```rust,ignore
+//! let consumer = OutboxConsumer::new(pool.clone(), "outbox");
+//! let mut events = pin!(consumer.stream());
+//! while let Some(event) = events.try_next().await? {
+//!     notify(&event).await?;
+//!     consumer.acknowledge(&event).await?;
+//! }
+//! ```
+//!
+//! The outbox table must have the following columns, where `payload` is a
+//! JSON object of the row after the change, or before it for deletions:
+//!
+//! ```sql
+//! CREATE TABLE outbox (
+//!     id INTEGER PRIMARY KEY AUTOINCREMENT,
+//!     table_name TEXT NOT NULL,
+//!     entity_id TEXT NOT NULL,
+//!     operation TEXT NOT NULL,
+//!     payload TEXT NOT NULL,
+//!     created_at TEXT NOT NULL,
+//!     acknowledged_at TEXT
+//! );
+//! ```
+//!
+//! ## Axum
+//!
+//! With the `axum` feature, `axum::router` builds the routes of a struct
+//! deriving every CRUD operation, its rows and the filter of `read_page`
+//! being sent as JSON through serde. It enables the `page` feature, which
+//! generates `read_page` and its filter. The routes take the pool as state:
+//!
+//! ```rust,ignore
+//! let app = Router::new()
+//!     .nest("/users", derive_crud::axum::router::<User>())
+//!     .with_state(pool);
+//! ```
+//!
+//! `GET /users?name=Debbie&limit=10&offset=20` lists the rows, `POST /users`
+//! creates one, and `GET`, `PUT` and `DELETE` on `/users/{id}` read, update
+//! and delete one. Errors answer with the status of their kind: `404 Not
+//! Found`, `409 Conflict`, `422 Unprocessable Entity` for validation errors,
+//! or `500 Internal Server Error`.
+//!
+//! ## GraphQL
+//!
+//! With the `graphql` feature, `#[derive(GraphQL)]` makes a struct deriving
+//! every CRUD operation an `async-graphql` object type, and generates
+//! `UserQuery` with `user(id)` and `users(first, after, filter)`,
+//! `UserMutation` with `createUser`, `updateUser` and `deleteUser`, and a
+//! `UserLoader` batching the lookups by ID. The resolvers read the pool and
+//! the dataloader from the data of the schema:
+//!
+//! ```rust,ignore
+//! #[derive(MergedObject, Default)]
+//! struct Query(UserQuery, PostQuery);
+//!
+//! let schema = Schema::build(Query::default(), UserMutation, EmptySubscription)
+//!     .data(DataLoader::new(UserLoader::new(pool.clone()), tokio::spawn))
+//!     .data(pool)
+//!     .finish();
+//! ```
+//!
+//! ## Tracing
+//!
+//! With the `tracing` feature, every generated query function runs inside an
+//! `INFO` span named after it, such as `User::read_one`, with the fields
+//! `entity`, `table`, `operation`, `id` (when the function receives or returns
+//! a key), `rows_affected` and, on failure, the `error` kind.
+//!
+//! ## Observers
+//!
+//! Every generated query function reports its entity, table, operation,
+//! duration, rows and outcome to the [`observer::CrudObserver`] set once for
+//! the program with [`observer::set_observer`]. A single pool can report to
+//! its own observer instead by wrapping it in an
+//! [`observer::ObservedRepository`]: the calls made through its repository
+//! traits are reported to that observer only. With the `metrics` feature,
+//! `observer::MetricsObserver` records them with the `metrics` crate:
+//!
+//! ```rust,ignore
+//! derive_crud::observer::set_observer(derive_crud::observer::MetricsObserver)?;
+//! ```
+//!
+//! ## Offline builds
+//!
+//! The generated queries are checked against the database given in
+//! `DATABASE_URL` while compiling. Builds without a database, such as CI or
+//! docs.rs, can use the query cache of SQLx instead, since the SQL generated
+//! for a struct only depends on its definition:
+//!
+//! 1. Run `cargo sqlx prepare` with `DATABASE_URL` set, which writes the
+//!    `.sqlx` directory at the root of the crate (or workspace).
+//! 2. Commit the `.sqlx` directory, and prepare it again whenever a derived
+//!    struct or its table changes.
+//! 3. Build with `SQLX_OFFLINE=true`, which reads the queries from `.sqlx`
+//!    without connecting to a database.
+//!
+//! The query cache of this repository's tests is refreshed by running them
+//! with `SQLX_OFFLINE_DIR` pointing at an empty `.sqlx` directory:
+//!
+//! ```sh
+//! rm -rf .sqlx target/tests/trybuild && mkdir .sqlx
+//! SQLX_OFFLINE_DIR="$PWD/.sqlx" cargo test --workspace
+//! ```
+
+#[cfg(feature = "axum")]
+pub use crud_axum as axum;
+pub use crud_core::audit;
+pub use crud_core::entity::{Entity, Filterable};
+pub use crud_core::error::{CRUDError, CRUDErrorKind, FieldError};
+#[cfg(feature = "graphql")]
+pub use crud_core::graphql;
+pub use crud_core::hooks::{
+    AfterCreate, AfterDelete, AfterUpdate, BeforeCreate, BeforeDelete, BeforeUpdate, CreateValues,
+};
+pub use crud_core::json;
+pub use crud_core::migration;
+#[cfg(feature = "mock")]
+pub use crud_core::mock;
+pub use crud_core::observer;
+pub use crud_core::outbox;
+pub use crud_core::registry::{
+    registered_entities, registered_entities_for, ColumnMeta, EntityDerives, EntityFlags,
+    EntityMeta, EntityRegistration,
+};
+pub use crud_core::repository::{
+    CreateRepository, DeleteRepository, Page, PageRepository, ReadRepository, Repository,
+    UpdateRepository,
+};
+pub use crud_core::schema::{registered_schemas, ColumnSchema, ForeignKey, Schema, TableSchema};
+#[cfg(feature = "tracing")]
+pub use crud_core::trace;
+pub use crud_core::validation;
 
-pub use crud_core::error::CRUDError;
+#[cfg(feature = "cli")]
+pub mod cli;
 
-pub use crud_macro::{Create, Delete, Read, Update};
+#[cfg(feature = "graphql")]
+pub use crud_macro::GraphQL;
+pub use crud_macro::{Create, Delete, Read, Schema, Update};
 
 // Re-export to ensure crate dependencies are available at compilation.
+#[cfg(feature = "graphql")]
+pub use async_graphql;
 pub use async_stream;
 pub use futures_core;
 pub use futures_util;
+pub use inventory;
+#[cfg(feature = "serde")]
+pub use serde;
+pub use serde_json;
 pub use sqlx;
+#[cfg(feature = "tracing")]
+pub use tracing;
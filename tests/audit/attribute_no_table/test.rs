@@ -0,0 +1,12 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("customer_table")]
+#[crud_audit]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,88 @@
+use derive_crud::audit::AuditContext;
+use derive_crud::{CRUDErrorKind, Create, Delete, Read, Update};
+
+#[derive(Create, Read, Update, Delete, Debug)]
+#[crud_table("customer_table")]
+#[crud_audit("audit_log")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+/// Entity ID, operation, before, after and actor of every audit row.
+type AuditRow = (String, String, Option<String>, Option<String>, Option<String>);
+
+async fn audit_rows(pool: &sqlx::SqlitePool) -> Vec<AuditRow> {
+    sqlx::query_as(
+        "SELECT entity_id, operation, before, after, actor FROM audit_log \
+         WHERE table_name = 'customer_table' AND created_at <> '' ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let alice = AuditContext::actor("alice");
+    let mut debbie = Customer::create_audited(&pool, &alice, "Debbie".to_string())
+        .await
+        .unwrap();
+    let id = debbie.id.to_string();
+    assert_eq!(
+        audit_rows(&pool).await,
+        vec![(
+            id.clone(),
+            "create".to_string(),
+            None,
+            Some(format!(r#"{{"id":{id},"name":"Debbie"}}"#)),
+            Some("alice".to_string()),
+        )]
+    );
+
+    // Updates only keep the changed fields, and the plain functions have no actor.
+    debbie.name = "Deborah".to_string();
+    debbie.update(&pool).await.unwrap();
+    assert_eq!(
+        audit_rows(&pool).await[1],
+        (
+            id.clone(),
+            "update".to_string(),
+            Some(r#"{"name":"Debbie"}"#.to_string()),
+            Some(r#"{"name":"Deborah"}"#.to_string()),
+            None,
+        )
+    );
+
+    // Audit rows are written in the transaction of the change.
+    let mut tx = pool.begin().await.unwrap();
+    Customer::create_audited(&mut *tx, &alice, "Rolled back".to_string())
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+    assert_eq!(audit_rows(&pool).await.len(), 2);
+
+    Customer::delete_audited(&pool, &AuditContext::actor("bob"), debbie.id)
+        .await
+        .unwrap();
+
+    // Changes to a missing row are not audited.
+    let error = Customer::delete(&pool, debbie.id).await.unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::NotFound);
+    let error = debbie.update(&pool).await.unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::NotFound);
+    assert_eq!(
+        audit_rows(&pool).await[2..],
+        [(
+            id.clone(),
+            "delete".to_string(),
+            Some(format!(r#"{{"id":{id},"name":"Deborah"}}"#)),
+            None,
+            Some("bob".to_string()),
+        )]
+    );
+}
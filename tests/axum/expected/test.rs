@@ -0,0 +1,108 @@
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use derive_crud::{Create, Delete, Read, Update};
+use http_body_util::BodyExt;
+use serde::{Deserialize, Serialize};
+use tower::ServiceExt;
+
+#[derive(Create, Read, Update, Delete, Serialize, Deserialize, Debug, PartialEq)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    #[serde(default)]
+    id: i64,
+    #[crud_validate(length(min = 1))]
+    name: String,
+}
+
+async fn send(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<serde_json::Value>,
+) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    let body = match body {
+        Some(body) => Body::from(body.to_string()),
+        None => Body::empty(),
+    };
+
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    // Rejections of axum extractors have a plain text body.
+    let body = match bytes.is_empty() {
+        true => serde_json::Value::Null,
+        false => serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&bytes).into()),
+    };
+    (status, body)
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+    let app = Router::new()
+        .nest("/customers", derive_crud::axum::router::<Customer>())
+        .with_state(pool);
+
+    let mut ids = Vec::new();
+    for name in ["Debbie", "Carol", "Debbie"] {
+        let body = serde_json::json!({ "name": name });
+        let (status, created) = send(&app, Method::POST, "/customers", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["name"], name);
+        ids.push(created["id"].as_i64().unwrap());
+    }
+
+    let (status, customer) = send(&app, Method::GET, &format!("/customers/{}", ids[1]), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(customer, serde_json::json!({ "id": ids[1], "name": "Carol" }));
+
+    // Lists are filtered by field and paginated in key order.
+    let (status, customers) = send(&app, Method::GET, "/customers?name=Debbie", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(customers.as_array().unwrap().len(), 2);
+    let (_, customers) = send(&app, Method::GET, "/customers?limit=1&offset=1", None).await;
+    assert_eq!(customers, serde_json::json!([{ "id": ids[1], "name": "Carol" }]));
+
+    let uri = format!("/customers/{}", ids[0]);
+    let body = serde_json::json!({ "id": ids[0], "name": "Deborah" });
+    let (status, updated) = send(&app, Method::PUT, &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["name"], "Deborah");
+
+    // Errors are mapped from their kind.
+    let body = serde_json::json!({ "id": ids[0], "name": "" });
+    let (status, error) = send(&app, Method::PUT, &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(error["fields"][0]["field"], "name");
+    let body = serde_json::json!({ "id": ids[1], "name": "Carol" });
+    let (status, error) = send(&app, Method::PUT, &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(error["fields"][0]["field"], "id");
+
+    let (status, body) = send(&app, Method::DELETE, &uri, None).await;
+    assert_eq!((status, body), (StatusCode::NO_CONTENT, serde_json::Value::Null));
+    let (status, error) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error, serde_json::json!({ "error": "no row matched the id" }));
+    let (status, _) = send(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let body = serde_json::json!({ "id": ids[0], "name": "Deborah" });
+    let (status, error) = send(&app, Method::PUT, &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error, serde_json::json!({ "error": "no row matched the update" }));
+
+    let (status, _) = send(&app, Method::GET, "/customers/not-a-number", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,62 @@
+use derive_crud::{cli, Schema};
+
+#[derive(Schema)]
+#[crud_table("cli_table")]
+struct Note {
+    #[crud_id]
+    id: i64,
+    body: String,
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    assert!(cli::run(args(&["--help"])).await.is_ok());
+    let error = cli::run(args(&["generate"])).await.unwrap_err();
+    assert!(error.starts_with("Unknown command `generate`"));
+    let error = cli::run(args(&["migrate", "--database-url"])).await.unwrap_err();
+    assert_eq!(error, "Missing value for `--database-url`");
+    let error = cli::run(args(&["migrate", "--force"])).await.unwrap_err();
+    assert_eq!(error, "Unexpected argument `--force`");
+
+    let directory = std::env::temp_dir().join(format!("derive_crud_cli_{}", std::process::id()));
+    let source = directory.to_str().unwrap();
+    cli::run(args(&["migrate", "--database-url", &database_url, "--source", source, "--dry-run"]))
+        .await
+        .unwrap();
+    assert!(!directory.exists());
+
+    cli::run(args(&["migrate", "--database-url", &database_url, "--source", source, "--name", "add notes"]))
+        .await
+        .unwrap();
+    let files: Vec<_> = std::fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].to_str().unwrap().ends_with("_add_notes.sql"));
+    assert_eq!(
+        std::fs::read_to_string(&files[0]).unwrap(),
+        "CREATE TABLE IF NOT EXISTS \"cli_table\" (id INTEGER PRIMARY KEY NOT NULL, body TEXT NOT NULL);\n"
+    );
+
+    sqlx::migrate::Migrator::new(directory.as_path())
+        .await
+        .unwrap()
+        .run(&pool)
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    // Only the tables of the structs are compared, so the others are left alone.
+    cli::run(args(&["migrate", "--database-url", &database_url, "--source", source]))
+        .await
+        .unwrap();
+    assert!(!directory.exists());
+}
@@ -0,0 +1,15 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("timestamp_table")]
+struct Test {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_created_at]
+    created_at: String,
+    #[crud_created_at]
+    updated_at: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("client_id_table")]
+struct Test {
+    #[crud_id(generated = "server")]
+    id: String,
+    name: String,
+}
+
+fn main() {}
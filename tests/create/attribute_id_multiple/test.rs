@@ -1,18 +0,0 @@
-use derive_crud::Create;
-
-#[derive(Create)]
-#[crud_table("test_table")]
-struct Test {
-    #[crud_id]
-    id: i64,
-    #[crud_id]
-    name: String,
-}
-
-#[tokio::main]
-async fn main() {
-    let database_url = std::env::var("DATABASE_URL").unwrap();
-    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
-
-    let _ = Test::create(&pool, "Debbie".to_string()).await;
-}
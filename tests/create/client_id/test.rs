@@ -0,0 +1,21 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("client_id_table")]
+struct Test {
+    #[crud_id(generated = "client")]
+    id: String,
+    name: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let item = Test::create(&pool, "client-key".to_string(), "Debbie".to_string())
+        .await
+        .unwrap();
+    assert_eq!(item.id, "client-key");
+    assert_eq!(item.name, "Debbie");
+}
@@ -0,0 +1,29 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use derive_crud::Create;
+
+static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+
+fn next_key() -> String {
+    format!("generated-{}", NEXT_KEY.fetch_add(1, Ordering::SeqCst))
+}
+
+#[derive(Create)]
+#[crud_table("client_id_table")]
+struct Test {
+    #[crud_id(generated = "client", generator = "next_key()")]
+    id: String,
+    name: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let first = Test::create(&pool, "Debbie".to_string()).await.unwrap();
+    let second = Test::create(&pool, "Deborah".to_string()).await.unwrap();
+    assert_eq!(first.id, "generated-0");
+    assert_eq!(second.id, "generated-1");
+    assert_eq!(second.name, "Deborah");
+}
@@ -0,0 +1,23 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("composite_table")]
+struct Test {
+    #[crud_id]
+    user_id: i64,
+    #[crud_id]
+    role_id: i64,
+    note: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let item = Test::create(&pool, 1, 2, "Debbie".to_string())
+        .await
+        .unwrap();
+    assert_eq!((item.user_id, item.role_id), (1, 2));
+    assert_eq!(item.note, "Debbie");
+}
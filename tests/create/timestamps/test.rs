@@ -0,0 +1,25 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("timestamp_table")]
+struct Test {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_created_at]
+    created_at: String,
+    #[crud_updated_at]
+    updated_at: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let item = Test::create(&pool, "Debbie".to_string()).await.unwrap();
+    assert_eq!(item.name, "Debbie");
+    assert!(!item.created_at.is_empty());
+    assert_eq!(item.created_at, item.updated_at);
+    let _ = item.id;
+}
@@ -0,0 +1,25 @@
+use derive_crud::{Create, Delete, Read};
+
+#[derive(Create, Read, Delete)]
+#[crud_table("composite_table")]
+struct Test {
+    #[crud_id]
+    user_id: i64,
+    #[crud_id]
+    role_id: i64,
+    note: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    Test::create(&pool, 5, 1, "first".to_string()).await.unwrap();
+    Test::create(&pool, 5, 2, "second".to_string()).await.unwrap();
+
+    Test::delete(&pool, (5, 1)).await.unwrap();
+
+    assert!(Test::read_one(&pool, (5, 1)).await.is_err());
+    assert!(Test::read_one(&pool, (5, 2)).await.is_ok());
+}
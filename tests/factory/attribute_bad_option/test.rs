@@ -0,0 +1,17 @@
+use crud_test::Factory;
+use derive_crud::Create;
+
+#[derive(Create, Factory)]
+#[crud_table("member_table")]
+struct Member {
+    #[crud_id]
+    id: i64,
+    #[factory(value = "member@example.com")]
+    email: String,
+    name: String,
+    age: i64,
+    active: bool,
+    nickname: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,55 @@
+#![deny(private_interfaces)]
+
+use crud_test::Factory;
+use derive_crud::Create;
+
+#[derive(Create, Factory)]
+#[crud_table("member_table")]
+struct Member {
+    #[crud_id]
+    id: i64,
+    #[factory(sequence = "member_{n}@example.com")]
+    email: String,
+    name: String,
+    #[factory(default = "30")]
+    age: i64,
+    active: bool,
+    nickname: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let first = Member::factory().insert(&pool).await.unwrap();
+    assert!(first.email.starts_with("member_"));
+    assert!(first.email.ends_with("@example.com"));
+    assert!(first.name.starts_with("name_"));
+    assert_eq!(first.age, 30);
+    assert!(!first.active);
+    assert_eq!(first.nickname, None);
+
+    let debbie = Member::factory()
+        .name("Debbie")
+        .active(true)
+        .nickname(Some("Deb".to_string()))
+        .insert(&pool)
+        .await
+        .unwrap();
+    assert_eq!(debbie.name, "Debbie");
+    assert!(debbie.active);
+    assert_eq!(debbie.nickname.as_deref(), Some("Deb"));
+    assert_ne!(debbie.email, first.email);
+
+    let mut tx = pool.begin().await.unwrap();
+    let members = Member::factory()
+        .age(47)
+        .insert_many(&mut *tx, 3)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    assert_eq!(members.len(), 3);
+    assert!(members.iter().all(|member| member.age == 47));
+    assert!(members.windows(2).all(|pair| pair[0].email != pair[1].email));
+}
@@ -0,0 +1,2 @@
+INSERT INTO customer_table (name) VALUES ('Debbie');
+INSERT INTO customer_table (name) VALUES ('Bob');
@@ -0,0 +1,12 @@
+use derive_crud::{Create, Delete, GraphQL, Read, Update};
+
+#[derive(Create, Read, Update, Delete, GraphQL, Clone)]
+#[crud_table("customer_table")]
+#[crud_graphql(plural = "clients", singular = "client")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,133 @@
+use derive_crud::async_graphql::dataloader::DataLoader;
+use derive_crud::async_graphql::{EmptySubscription, Request, Schema, Variables};
+use derive_crud::{Create, Delete, GraphQL, Read, Update};
+
+#[derive(Create, Read, Update, Delete, GraphQL, Clone, Debug)]
+#[crud_table("member_table")]
+struct Member {
+    #[crud_id]
+    id: i64,
+    /// Unique address of the member.
+    #[crud_validate(email)]
+    email: String,
+    name: String,
+    age: i64,
+    active: bool,
+    nickname: Option<String>,
+}
+
+type MemberSchema = Schema<MemberQuery, MemberMutation, EmptySubscription>;
+
+async fn execute(schema: &MemberSchema, query: &str, variables: serde_json::Value) -> serde_json::Value {
+    let request = Request::new(query).variables(Variables::from_json(variables));
+    serde_json::to_value(schema.execute(request).await).unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+    let schema = Schema::build(MemberQuery, MemberMutation, EmptySubscription)
+        .data(DataLoader::new(MemberLoader::new(pool.clone()), tokio::spawn))
+        .data(pool)
+        .finish();
+
+    let mut ids = Vec::new();
+    for (name, age) in [("Debbie", 47), ("Carol", 31), ("Bob", 47)] {
+        let response = execute(
+            &schema,
+            "mutation ($input: CreateMemberInput!) { createMember(input: $input) { id name } }",
+            serde_json::json!({ "input": {
+                "email": format!("{}@example.com", name.to_lowercase()),
+                "name": name,
+                "age": age,
+                "active": true,
+                "nickname": null,
+            }}),
+        )
+        .await;
+        assert_eq!(response["data"]["createMember"]["name"], name, "{response}");
+        ids.push(response["data"]["createMember"]["id"].as_i64().unwrap());
+    }
+
+    // Lookups by ID go through the dataloader, and descriptions come from doc comments.
+    let response = execute(
+        &schema,
+        "query ($a: Int!, $b: Int!) { a: member(id: $a) { name } b: member(id: $b) { name } }",
+        serde_json::json!({ "a": ids[0], "b": ids[2] }),
+    )
+    .await;
+    assert_eq!(
+        response["data"],
+        serde_json::json!({ "a": { "name": "Debbie" }, "b": { "name": "Bob" } })
+    );
+    assert!(schema.sdl().contains("Unique address of the member."));
+
+    let response = execute(
+        &schema,
+        "{ members(first: 1, filter: { age: 47 }) { edges { cursor node { name } } pageInfo { hasNextPage } } }",
+        serde_json::json!({}),
+    )
+    .await;
+    let members = &response["data"]["members"];
+    assert_eq!(members["edges"][0]["node"]["name"], "Debbie");
+    assert_eq!(members["pageInfo"]["hasNextPage"], true);
+    let cursor = members["edges"][0]["cursor"].clone();
+    let response = execute(
+        &schema,
+        "query ($after: String) { members(first: 5, after: $after, filter: { age: 47 }) { edges { node { name } } pageInfo { hasNextPage hasPreviousPage } } }",
+        serde_json::json!({ "after": cursor }),
+    )
+    .await;
+    let members = &response["data"]["members"];
+    assert_eq!(members["edges"], serde_json::json!([{ "node": { "name": "Bob" } }]));
+    assert_eq!(
+        members["pageInfo"],
+        serde_json::json!({ "hasNextPage": false, "hasPreviousPage": true })
+    );
+
+    let response = execute(
+        &schema,
+        "mutation ($id: Int!) { updateMember(id: $id, input: { nickname: \"Deb\" }) { name nickname } }",
+        serde_json::json!({ "id": ids[0] }),
+    )
+    .await;
+    assert_eq!(
+        response["data"]["updateMember"],
+        serde_json::json!({ "name": "Debbie", "nickname": "Deb" })
+    );
+
+    // Errors carry their kind.
+    let response = execute(
+        &schema,
+        "mutation ($id: Int!) { updateMember(id: $id, input: { email: \"debbie\" }) { name } }",
+        serde_json::json!({ "id": ids[0] }),
+    )
+    .await;
+    let extensions = &response["errors"][0]["extensions"];
+    assert_eq!(extensions["code"], "VALIDATION");
+    assert_eq!(extensions["fields"][0]["field"], "email");
+
+    let response = execute(
+        &schema,
+        "mutation ($id: Int!) { deleteMember(id: $id) { name } }",
+        serde_json::json!({ "id": ids[1] }),
+    )
+    .await;
+    assert_eq!(response["data"]["deleteMember"]["name"], "Carol");
+    let response = execute(
+        &schema,
+        "mutation ($id: Int!) { deleteMember(id: $id) { name } }",
+        serde_json::json!({ "id": ids[1] }),
+    )
+    .await;
+    assert_eq!(response["errors"][0]["extensions"]["code"], "NOT_FOUND");
+
+    let response = execute(
+        &schema,
+        "query ($id: Int!) { member(id: $id) { name } }",
+        serde_json::json!({ "id": ids[1] }),
+    )
+    .await;
+    assert_eq!(response["data"]["member"], serde_json::Value::Null);
+}
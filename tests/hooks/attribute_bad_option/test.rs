@@ -0,0 +1,12 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("customer_table")]
+#[crud_hooks(before_insert)]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+fn main() {}
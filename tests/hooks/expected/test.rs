@@ -0,0 +1,134 @@
+use std::sync::Mutex;
+
+use derive_crud::sqlx::SqliteConnection;
+use derive_crud::{
+    AfterCreate, AfterDelete, BeforeCreate, BeforeDelete, BeforeUpdate, CRUDError, Create, Delete,
+    Read, Update,
+};
+
+#[derive(Create, Read, Update, Delete, Debug)]
+#[crud_table("customer_table")]
+#[crud_hooks(before_create, after_create, before_update)]
+#[crud_hooks(before_delete, after_delete)]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+#[derive(Create, Read, Delete, Debug)]
+#[crud_table("customer_table")]
+#[crud_hooks(after_create, after_delete)]
+struct Rejected {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+impl AfterCreate for Rejected {
+    async fn after_create(&self, _connection: &mut SqliteConnection) -> Result<(), CRUDError> {
+        Err(CRUDError::new("rejected"))
+    }
+}
+
+impl AfterDelete for Rejected {
+    async fn after_delete(_connection: &mut SqliteConnection, _id: &i64) -> Result<(), CRUDError> {
+        Err(CRUDError::new("rejected"))
+    }
+}
+
+/// Keys of the deleted customers.
+static DELETED: Mutex<Vec<i64>> = Mutex::new(Vec::new());
+
+impl BeforeCreate for Customer {
+    async fn before_create(
+        _connection: &mut SqliteConnection,
+        values: &mut NewCustomer,
+    ) -> Result<(), CRUDError> {
+        values.name = values.name.trim().to_lowercase();
+        match values.name.is_empty() {
+            true => Err(CRUDError::new("name must not be empty")),
+            false => Ok(()),
+        }
+    }
+}
+
+impl AfterCreate for Customer {
+    async fn after_create(&self, connection: &mut SqliteConnection) -> Result<(), CRUDError> {
+        sqlx::query("INSERT INTO order_table (customer_id, item) VALUES (?, 'Welcome gift')")
+            .bind(self.id)
+            .execute(connection)
+            .await
+            .map_err(|e| CRUDError::new(e.to_string()))?;
+        Ok(())
+    }
+}
+
+impl BeforeUpdate for Customer {
+    async fn before_update(&self, _connection: &mut SqliteConnection) -> Result<(), CRUDError> {
+        match self.name.is_empty() {
+            true => Err(CRUDError::new("name must not be empty")),
+            false => Ok(()),
+        }
+    }
+}
+
+impl BeforeDelete for Customer {
+    async fn before_delete(connection: &mut SqliteConnection, id: &i64) -> Result<(), CRUDError> {
+        sqlx::query("DELETE FROM order_table WHERE customer_id = ?")
+            .bind(id)
+            .execute(connection)
+            .await
+            .map_err(|e| CRUDError::new(e.to_string()))?;
+        Ok(())
+    }
+}
+
+impl AfterDelete for Customer {
+    async fn after_delete(_connection: &mut SqliteConnection, id: &i64) -> Result<(), CRUDError> {
+        DELETED.lock().unwrap().push(*id);
+        Ok(())
+    }
+}
+
+async fn count_orders(pool: &sqlx::SqlitePool, customer_id: i64) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM order_table WHERE customer_id = ?")
+        .bind(customer_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    // Before-hooks change the values and may abort.
+    let mut debbie = Customer::create(&pool, "  Debbie ".to_string()).await.unwrap();
+    assert_eq!(debbie.name, "debbie");
+    assert_eq!(count_orders(&pool, debbie.id).await, 1);
+    assert!(Customer::create(&pool, "   ".to_string()).await.is_err());
+
+    debbie.name = String::new();
+    assert!(debbie.update(&pool).await.is_err());
+    assert_eq!(Customer::read_one(&pool, debbie.id).await.unwrap().name, "debbie");
+    debbie.name = "deborah".to_string();
+    debbie.update(&pool).await.unwrap();
+
+    // Hooks run in the transaction of the operation.
+    let mut tx = pool.begin().await.unwrap();
+    let rolled_back = Customer::create(&mut *tx, "Rolled back".to_string()).await.unwrap();
+    tx.rollback().await.unwrap();
+    assert_eq!(count_orders(&pool, rolled_back.id).await, 0);
+
+    // A failing after-hook rolls back the change, even on a pool.
+    assert!(Rejected::create(&pool, "Rejected".to_string()).await.is_err());
+    assert!(Rejected::read_all(&pool).await.unwrap().iter().all(|c| c.name != "Rejected"));
+    assert!(Rejected::delete(&pool, debbie.id).await.is_err());
+    assert!(Rejected::read_one(&pool, debbie.id).await.is_ok());
+
+    Customer::delete(&pool, debbie.id).await.unwrap();
+    assert_eq!(count_orders(&pool, debbie.id).await, 0);
+    assert_eq!(*DELETED.lock().unwrap(), vec![debbie.id]);
+}
@@ -0,0 +1,12 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("profile_table")]
+struct Profile {
+    #[crud_id]
+    #[crud_json]
+    id: i64,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use derive_crud::Read;
+
+// The column of a `#[crud_json]` field must still hold text.
+#[derive(Read)]
+#[crud_table("versioned_table")]
+struct Versioned {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_json]
+    version: Vec<i64>,
+}
+
+fn main() {}
@@ -0,0 +1,83 @@
+use derive_crud::{Create, Read, Update};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Settings {
+    theme: String,
+    notifications: bool,
+}
+
+#[derive(Create, Read, Update, Debug, PartialEq)]
+#[crud_table("profile_table")]
+#[crud_audit("audit_log")]
+struct Profile {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_json]
+    tags: Vec<String>,
+    #[crud_json]
+    settings: Option<Settings>,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let settings = Settings {
+        theme: "dark".to_string(),
+        notifications: true,
+    };
+    let mut debbie = Profile::create(
+        &pool,
+        "Debbie".to_string(),
+        vec!["admin".to_string(), "editor".to_string()],
+        Some(settings.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(debbie.tags, ["admin", "editor"]);
+    assert_eq!(debbie.settings, Some(settings));
+
+    // The fields are stored as JSON text.
+    let (tags, stored_settings): (String, Option<String>) =
+        sqlx::query_as("SELECT tags, settings FROM profile_table WHERE id = ?")
+            .bind(debbie.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(tags, r#"["admin","editor"]"#);
+    assert_eq!(
+        stored_settings.as_deref(),
+        Some(r#"{"theme":"dark","notifications":true}"#)
+    );
+
+    debbie.tags.push("owner".to_string());
+    debbie.settings = None;
+    debbie.update(&pool).await.unwrap();
+    assert_eq!(Profile::read_one(&pool, debbie.id).await.unwrap(), debbie);
+
+    let alice = Profile::create(&pool, "Alice".to_string(), Vec::new(), None)
+        .await
+        .unwrap();
+    let profiles = Profile::read_all(&pool).await.unwrap();
+    assert_eq!(profiles, [debbie, alice]);
+
+    // Audit snapshots nest the fields as JSON.
+    let after: String = sqlx::query_scalar(
+        "SELECT after FROM audit_log WHERE table_name = 'profile_table' AND operation = 'update'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(after, r#"{"settings":null,"tags":["admin","editor","owner"]}"#);
+
+    // Text which is not valid JSON fails to decode.
+    sqlx::query("UPDATE profile_table SET tags = 'admin' WHERE id = ?")
+        .bind(profiles[1].id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(Profile::read_one(&pool, profiles[1].id).await.is_err());
+}
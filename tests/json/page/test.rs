@@ -0,0 +1,42 @@
+use derive_crud::{Create, Page, Read};
+
+#[derive(Create, Read, Debug, PartialEq)]
+#[crud_table("profile_table")]
+struct Profile {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_json]
+    tags: Vec<String>,
+    #[crud_json]
+    settings: Option<Vec<String>>,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    Profile::create(&pool, "Debbie".to_string(), vec!["admin".to_string()], None)
+        .await
+        .unwrap();
+    Profile::create(&pool, "Alice".to_string(), Vec::new(), None)
+        .await
+        .unwrap();
+
+    // The filter leaves out the JSON fields.
+    let filter = ProfileFilter {
+        name: Some("Alice".to_string()),
+        ..Default::default()
+    };
+    let page = Profile::read_page(&pool, &filter, Page::default())
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert!(page[0].tags.is_empty());
+
+    sqlx::query("DELETE FROM profile_table")
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,183 @@
+use derive_crud::migration::{self, Migration};
+use derive_crud::Schema;
+
+#[derive(Schema)]
+#[crud_table("constraint_table")]
+struct Current {
+    #[crud_id]
+    id: i64,
+    #[crud_column(default = "0")]
+    score: i64,
+    #[crud_column(unique)]
+    code: String,
+    #[crud_column(check = "age >= 0")]
+    age: i64,
+    #[crud_column(references = "customer_table(id)")]
+    owner_id: Option<i64>,
+    note: Option<String>,
+}
+
+#[derive(Schema)]
+#[crud_table("constraint_table")]
+struct ChangedDefault {
+    #[crud_id]
+    id: i64,
+    #[crud_column(default = "1")]
+    score: i64,
+    #[crud_column(unique)]
+    code: String,
+    #[crud_column(check = "age >= 0")]
+    age: i64,
+    #[crud_column(references = "customer_table(id)")]
+    owner_id: Option<i64>,
+    note: Option<String>,
+}
+
+#[derive(Schema)]
+#[crud_table("constraint_table")]
+struct ChangedUnique {
+    #[crud_id]
+    id: i64,
+    #[crud_column(default = "0")]
+    score: i64,
+    code: String,
+    #[crud_column(check = "age >= 0")]
+    age: i64,
+    #[crud_column(references = "customer_table(id)")]
+    owner_id: Option<i64>,
+    note: Option<String>,
+}
+
+#[derive(Schema)]
+#[crud_table("constraint_table")]
+struct ChangedCheck {
+    #[crud_id]
+    id: i64,
+    #[crud_column(default = "0")]
+    score: i64,
+    #[crud_column(unique)]
+    code: String,
+    #[crud_column(check = "age >= 18")]
+    age: i64,
+    #[crud_column(references = "customer_table(id)")]
+    owner_id: Option<i64>,
+    note: Option<String>,
+}
+
+#[derive(Schema)]
+#[crud_table("constraint_table")]
+struct ChangedReferences {
+    #[crud_id]
+    id: i64,
+    #[crud_column(default = "0")]
+    score: i64,
+    #[crud_column(unique)]
+    code: String,
+    #[crud_column(check = "age >= 0")]
+    age: i64,
+    #[crud_column(references = "member_table(id)")]
+    owner_id: Option<i64>,
+    note: Option<String>,
+}
+
+#[derive(Schema)]
+#[crud_table("constraint_table")]
+struct RequiredNote {
+    #[crud_id]
+    id: i64,
+    #[crud_column(default = "0")]
+    score: i64,
+    #[crud_column(unique)]
+    code: String,
+    #[crud_column(check = "age >= 0")]
+    age: i64,
+    #[crud_column(references = "customer_table(id)")]
+    owner_id: Option<i64>,
+    #[crud_column(default = "''")]
+    note: String,
+}
+
+#[derive(Schema)]
+#[crud_table("constraint_table")]
+struct AddedRequired {
+    #[crud_id]
+    id: i64,
+    #[crud_column(default = "0")]
+    score: i64,
+    #[crud_column(unique)]
+    code: String,
+    #[crud_column(check = "age >= 0")]
+    age: i64,
+    #[crud_column(references = "customer_table(id)")]
+    owner_id: Option<i64>,
+    note: Option<String>,
+    #[crud_column(unique)]
+    email: String,
+}
+
+async fn run(pool: &sqlx::SqlitePool, migration: &Migration) {
+    let mut tx = pool.begin().await.unwrap();
+    for statement in &migration.statements {
+        sqlx::query(statement).execute(&mut *tx).await.unwrap();
+    }
+    tx.commit().await.unwrap();
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    sqlx::query(&Current::SCHEMA.create_table_sql())
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO constraint_table (code, age) VALUES ('a', 30)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(migration::diff(&pool, &[&Current::SCHEMA])
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Changed constraints rebuild the table.
+    for schema in [
+        &ChangedDefault::SCHEMA,
+        &ChangedUnique::SCHEMA,
+        &ChangedCheck::SCHEMA,
+        &ChangedReferences::SCHEMA,
+    ] {
+        let migration = migration::diff(&pool, &[schema]).await.unwrap();
+        assert_eq!(
+            migration.statements.first().map(String::as_str),
+            Some("PRAGMA defer_foreign_keys = ON"),
+            "{schema:?}"
+        );
+    }
+
+    // Columns becoming NOT NULL are filled with their default.
+    let migration = migration::diff(&pool, &[&RequiredNote::SCHEMA]).await.unwrap();
+    assert!(migration.statements.contains(
+        &"INSERT INTO \"constraint_table__new\" (id, score, code, age, owner_id, note) SELECT id, score, code, age, owner_id, COALESCE(note, '') FROM \"constraint_table\"".to_string()
+    ));
+    run(&pool, &migration).await;
+    let note: String = sqlx::query_scalar("SELECT note FROM constraint_table")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(note, "");
+    assert!(migration::diff(&pool, &[&RequiredNote::SCHEMA])
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Added NOT NULL columns without a default have no value for the copied rows.
+    let error = migration::diff(&pool, &[&AddedRequired::SCHEMA])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "cannot add the NOT NULL column `email` to the existing rows of `constraint_table` without a DEFAULT"
+    );
+}
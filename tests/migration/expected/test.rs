@@ -0,0 +1,70 @@
+use derive_crud::{migration, Schema};
+
+#[derive(Schema)]
+#[crud_table("migration_table")]
+struct Account {
+    #[crud_id]
+    id: i64,
+    email: String,
+    nickname: Option<String>,
+    #[crud_column(default = "0")]
+    balance: f64,
+}
+
+#[derive(Schema)]
+#[crud_table("rebuilt_table")]
+struct Profile {
+    #[crud_id]
+    id: i64,
+    #[crud_column(unique)]
+    handle: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    sqlx::query("CREATE TABLE migration_table (id INTEGER PRIMARY KEY, email TEXT NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("CREATE TABLE rebuilt_table (id INTEGER PRIMARY KEY, handle TEXT, bio TEXT)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO rebuilt_table (handle, bio) VALUES ('debbie', 'Hello')")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let migration = migration::diff_registered(&pool).await.unwrap();
+    assert_eq!(
+        migration.statements,
+        vec![
+            "ALTER TABLE \"migration_table\" ADD COLUMN nickname TEXT",
+            "ALTER TABLE \"migration_table\" ADD COLUMN balance REAL NOT NULL DEFAULT 0",
+            "PRAGMA defer_foreign_keys = ON",
+            "CREATE TABLE \"rebuilt_table__new\" (id INTEGER PRIMARY KEY NOT NULL, handle TEXT NOT NULL UNIQUE)",
+            "INSERT INTO \"rebuilt_table__new\" (id, handle) SELECT id, handle FROM \"rebuilt_table\"",
+            "DROP TABLE \"rebuilt_table\"",
+            "ALTER TABLE \"rebuilt_table__new\" RENAME TO \"rebuilt_table\"",
+        ]
+    );
+
+    let directory = std::env::temp_dir().join(format!("derive_crud_migrations_{}", std::process::id()));
+    let path = migration.write(&directory, "add accounts").unwrap();
+    assert!(path.to_str().unwrap().ends_with("_add_accounts.sql"));
+
+    let migrator = sqlx::migrate::Migrator::new(directory.as_path()).await.unwrap();
+    migrator.run(&pool).await.unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert!(migration::diff_registered(&pool).await.unwrap().is_empty());
+
+    let handle: String = sqlx::query_scalar("SELECT handle FROM rebuilt_table")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(handle, "debbie");
+}
@@ -0,0 +1 @@
+CREATE TABLE customer_table (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
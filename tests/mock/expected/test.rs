@@ -0,0 +1,114 @@
+use derive_crud::mock::MockRepository;
+use derive_crud::{
+    CRUDErrorKind, Create, CreateRepository, Delete, Read, ReadRepository, Repository, Update,
+    UpdateRepository,
+};
+
+#[derive(Create, Read, Update, Delete, Clone, Debug, PartialEq)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+#[derive(Create, Read, Update, Delete, Clone, Debug, PartialEq)]
+#[crud_table("versioned_table")]
+struct Versioned {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_version]
+    version: i64,
+}
+
+/// Business logic written against the repository traits.
+async fn rename<R: Repository<Customer>>(repository: &R, id: i64, name: &str) -> Customer {
+    let mut customer = repository.read_one(id).await.unwrap();
+    customer.name = name.to_string();
+    repository.update(&mut customer).await.unwrap();
+    customer
+}
+
+async fn exercise<R: Repository<Customer>>(repository: &R) {
+    let debbie = repository
+        .create(Customer {
+            id: 0,
+            name: "Debbie".to_string(),
+        })
+        .await
+        .unwrap();
+    let mut bob = repository
+        .create(Customer {
+            id: 0,
+            name: "Bob".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_ne!(debbie.id, bob.id);
+
+    let deborah = rename(repository, debbie.id, "Deborah").await;
+    assert_eq!(repository.read_one(debbie.id).await.unwrap(), deborah);
+
+    let all = repository.read_all().await.unwrap();
+    let position = |id| all.iter().position(|customer| customer.id == id).unwrap();
+    assert!(position(debbie.id) < position(bob.id));
+
+    // The mock and the database report a missing row alike.
+    repository.delete(&bob).await.unwrap();
+    assert_eq!(
+        repository.read_one(bob.id).await.unwrap_err().kind(),
+        CRUDErrorKind::NotFound
+    );
+    assert_eq!(
+        repository.update(&mut bob).await.unwrap_err().kind(),
+        CRUDErrorKind::NotFound
+    );
+    assert_eq!(
+        repository.delete(&bob).await.unwrap_err().kind(),
+        CRUDErrorKind::NotFound
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let mock = MockRepository::<Customer>::new();
+    exercise(&mock).await;
+    assert_eq!(mock.len(), 1);
+    assert_eq!(
+        mock.read_all().await.unwrap(),
+        [Customer {
+            id: 1,
+            name: "Deborah".to_string()
+        }]
+    );
+    assert_eq!(
+        mock.read_one(2).await.unwrap_err().kind(),
+        CRUDErrorKind::NotFound
+    );
+
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+    exercise(&pool).await;
+
+    let versions = MockRepository::<Versioned>::new();
+    let mut first = versions
+        .create(Versioned {
+            id: 0,
+            name: "Debbie".to_string(),
+            version: 0,
+        })
+        .await
+        .unwrap();
+    assert_eq!(first.version, 1);
+    let mut stale = first.clone();
+
+    first.name = "Deborah".to_string();
+    versions.update(&mut first).await.unwrap();
+    assert_eq!(first.version, 2);
+
+    stale.name = "Bob".to_string();
+    let error = versions.update(&mut stale).await.unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::Conflict);
+    assert_eq!(versions.read_one(first.id).await.unwrap().name, "Deborah");
+}
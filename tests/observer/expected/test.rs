@@ -0,0 +1,111 @@
+use std::sync::{Arc, Mutex};
+
+use derive_crud::observer::{self, CrudObserver, ObservedRepository, QueryEvent};
+use derive_crud::{CRUDErrorKind, Create, Delete, Read, ReadRepository, Update};
+use futures_util::StreamExt;
+
+#[derive(Create, Read, Update, Delete, Debug)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+/// Entity, table, operation, rows and outcome of every event.
+type Events = Arc<Mutex<Vec<(String, String, String, Option<u64>, Result<(), CRUDErrorKind>)>>>;
+
+/// Observer keeping every event.
+struct Recorder {
+    events: Events,
+}
+
+impl CrudObserver for Recorder {
+    fn observe(&self, event: &QueryEvent<'_>) {
+        self.events.lock().unwrap().push((
+            event.entity.to_string(),
+            event.table.to_string(),
+            event.operation.to_string(),
+            event.rows,
+            event.outcome,
+        ));
+    }
+}
+
+fn last(events: &Events) -> (String, Option<u64>, Result<(), CRUDErrorKind>) {
+    let (_, _, operation, rows, outcome) = events.lock().unwrap().last().cloned().unwrap();
+    (operation, rows, outcome)
+}
+
+#[tokio::main]
+async fn main() {
+    let events = Events::default();
+    observer::set_observer(Recorder {
+        events: events.clone(),
+    })
+    .unwrap();
+    assert!(observer::set_observer(Recorder {
+        events: Events::default(),
+    })
+    .is_err());
+
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let mut debbie = Customer::create(&pool, "Debbie".to_string()).await.unwrap();
+    {
+        let events = events.lock().unwrap();
+        let (entity, table, operation, rows, outcome) = events.last().unwrap();
+        assert_eq!(entity, "Customer");
+        assert_eq!(table, "customer_table");
+        assert_eq!(operation, "create");
+        assert_eq!(*rows, Some(1));
+        assert_eq!(*outcome, Ok(()));
+    }
+
+    let all = Customer::read_all(&pool).await.unwrap();
+    assert_eq!(last(&events), ("read_all".to_string(), Some(all.len() as u64), Ok(())));
+
+    let streamed: Vec<_> = Customer::read(&pool, debbie.id).collect().await;
+    assert_eq!(streamed.len(), 1);
+    assert_eq!(last(&events), ("read".to_string(), Some(1), Ok(())));
+
+    debbie.update(&pool).await.unwrap();
+    assert_eq!(last(&events), ("update".to_string(), Some(1), Ok(())));
+
+    Customer::delete(&pool, debbie.id).await.unwrap();
+    assert_eq!(last(&events), ("delete".to_string(), Some(1), Ok(())));
+
+    let error = Customer::read_one(&pool, debbie.id).await.unwrap_err();
+    assert_eq!(last(&events), ("read_one".to_string(), None, Err(error.kind())));
+
+    // A repository reports to its own observer instead of the global one.
+    let pool_events = Events::default();
+    let customers = ObservedRepository::new(
+        pool.clone(),
+        Recorder {
+            events: pool_events.clone(),
+        },
+    );
+    let count = events.lock().unwrap().len();
+    let all = ReadRepository::<Customer>::read_all(&customers).await.unwrap();
+    assert_eq!(last(&pool_events), ("read_all".to_string(), Some(all.len() as u64), Ok(())));
+    assert_eq!(pool_events.lock().unwrap().len(), 1);
+    assert_eq!(events.lock().unwrap().len(), count);
+
+    // With the same observer both ways, it sees each call once.
+    let customers = ObservedRepository::new(
+        pool.clone(),
+        Recorder {
+            events: events.clone(),
+        },
+    );
+    let count = events.lock().unwrap().len();
+    ReadRepository::<Customer>::read_all(&customers).await.unwrap();
+    assert_eq!(events.lock().unwrap().len(), count + 1);
+    assert_eq!(pool_events.lock().unwrap().len(), 1);
+
+    // Generated functions called afterwards are reported globally again.
+    Customer::read_all(&pool).await.unwrap();
+    assert_eq!(events.lock().unwrap().len(), count + 2);
+}
@@ -0,0 +1,71 @@
+use std::pin::pin;
+use std::time::Duration;
+
+use derive_crud::outbox::OutboxConsumer;
+use derive_crud::{Create, Delete, Update};
+use futures_util::TryStreamExt;
+
+#[derive(Create, Update, Delete, Debug)]
+#[crud_table("tag_table")]
+#[crud_audit("audit_log")]
+#[crud_outbox("outbox")]
+struct Tag {
+    #[crud_id]
+    id: i64,
+    label: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let mut rust = Tag::create(&pool, "rust".to_string()).await.unwrap();
+    let id = rust.id.to_string();
+    rust.label = "Rust".to_string();
+    rust.update(&pool).await.unwrap();
+
+    // Events are published in the transaction of the change.
+    let mut tx = pool.begin().await.unwrap();
+    Tag::create(&mut *tx, "rolled back".to_string())
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+
+    Tag::delete(&pool, rust.id).await.unwrap();
+    // Deleting a missing row publishes nothing.
+    assert!(Tag::delete(&pool, rust.id).await.is_err());
+
+    let consumer = OutboxConsumer::new(pool.clone(), "outbox")
+        .batch_size(2)
+        .poll_interval(Duration::from_millis(10));
+    let events = consumer.fetch(None).await.unwrap();
+    assert_eq!(events.len(), 2);
+
+    let mut stream = pin!(consumer.stream());
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let event = stream.try_next().await.unwrap().unwrap();
+        assert_eq!((event.table.as_str(), event.entity_id.as_str()), ("tag_table", id.as_str()));
+        received.push((event.operation.clone(), event.payload.clone()));
+        if event.operation != "update" {
+            consumer.acknowledge(&event).await.unwrap();
+        }
+    }
+    assert_eq!(
+        received,
+        [
+            ("create", format!(r#"{{"id":{id},"label":"rust"}}"#)),
+            ("update", format!(r#"{{"id":{id},"label":"Rust"}}"#)),
+            ("delete", format!(r#"{{"id":{id},"label":"Rust"}}"#)),
+        ]
+        .map(|(operation, payload)| (operation.to_string(), payload))
+    );
+
+    // Events which are not acknowledged are read again by the next consumer.
+    let events = consumer.fetch(None).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].operation, "update");
+    consumer.acknowledge(&events[0]).await.unwrap();
+    assert!(consumer.fetch(None).await.unwrap().is_empty());
+}
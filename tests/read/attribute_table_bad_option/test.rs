@@ -0,0 +1,11 @@
+use derive_crud::Read;
+
+#[derive(Read)]
+#[crud_table("test_table", checked = "no")]
+struct Test {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+fn main() {}
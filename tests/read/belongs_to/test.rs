@@ -0,0 +1,56 @@
+use derive_crud::{Create, Read};
+
+#[derive(Create, Read)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+#[derive(Create, Read)]
+#[crud_table("order_table")]
+struct Order {
+    #[crud_id]
+    id: i64,
+    #[crud_belongs_to(Customer)]
+    customer_id: i64,
+    #[crud_belongs_to(Customer)]
+    reviewer_id: Option<i64>,
+    item: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let debbie = Customer::create(&pool, "Debbie".to_string()).await.unwrap();
+    let bob = Customer::create(&pool, "Bob".to_string()).await.unwrap();
+    let carol = Customer::create(&pool, "Carol".to_string()).await.unwrap();
+
+    let order = Order::create(&pool, debbie.id, Some(bob.id), "Book".to_string())
+        .await
+        .unwrap();
+    Order::create(&pool, debbie.id, None, "Pen".to_string())
+        .await
+        .unwrap();
+    Order::create(&pool, bob.id, None, "Lamp".to_string())
+        .await
+        .unwrap();
+    Order::create(&pool, carol.id, None, "Desk".to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(order.customer(&pool).await.unwrap().name, "Debbie");
+    assert_eq!(order.reviewer(&pool).await.unwrap().unwrap().name, "Bob");
+
+    assert_eq!(debbie.orders(&pool).await.unwrap().len(), 2);
+    assert_eq!(bob.reviewer_orders(&pool).await.unwrap()[0].item, "Book");
+
+    let orders = Order::for_customers(&pool, &[debbie.id, bob.id])
+        .await
+        .unwrap();
+    assert_eq!(orders.len(), 3);
+    assert!(orders.iter().all(|order| order.customer_id != carol.id));
+}
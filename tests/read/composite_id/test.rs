@@ -0,0 +1,26 @@
+use derive_crud::{Create, Read};
+
+#[derive(Create, Read)]
+#[crud_table("composite_table")]
+struct Test {
+    #[crud_id]
+    user_id: i64,
+    #[crud_id]
+    role_id: i64,
+    note: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    Test::create(&pool, 3, 1, "first".to_string()).await.unwrap();
+    Test::create(&pool, 3, 2, "second".to_string()).await.unwrap();
+
+    let item = Test::read_one(&pool, (3, 2)).await.unwrap();
+    assert_eq!(item.note, "second");
+
+    let _ = Test::read(&pool, (3, 1));
+    let _ = Test::read_all(&pool);
+}
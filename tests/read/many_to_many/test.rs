@@ -0,0 +1,50 @@
+use derive_crud::{Create, Read};
+
+#[derive(Create, Read)]
+#[crud_table("tag_table")]
+struct Tag {
+    #[crud_id]
+    id: i64,
+    label: String,
+}
+
+#[derive(Create, Read)]
+#[crud_table("post_table")]
+#[crud_many_to_many(Tag, through = "post_tags", local = "post_id", remote = "tag_id")]
+struct Post {
+    #[crud_id]
+    id: i64,
+    title: String,
+}
+
+fn labels(tags: &[Tag]) -> Vec<&str> {
+    let mut labels: Vec<_> = tags.iter().map(|tag| tag.label.as_str()).collect();
+    labels.sort();
+    labels
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let post = Post::create(&pool, "Hello".to_string()).await.unwrap();
+    let rust = Tag::create(&pool, "rust".to_string()).await.unwrap();
+    let sql = Tag::create(&pool, "sql".to_string()).await.unwrap();
+    let web = Tag::create(&pool, "web".to_string()).await.unwrap();
+
+    assert!(post.tags(&pool).await.unwrap().is_empty());
+
+    post.add_tag(&pool, rust.id).await.unwrap();
+    post.add_tag(&pool, sql.id).await.unwrap();
+    assert_eq!(labels(&post.tags(&pool).await.unwrap()), ["rust", "sql"]);
+
+    post.remove_tag(&pool, rust.id).await.unwrap();
+    assert_eq!(labels(&post.tags(&pool).await.unwrap()), ["sql"]);
+
+    post.set_tags(&pool, &[rust.id, web.id]).await.unwrap();
+    assert_eq!(labels(&post.tags(&pool).await.unwrap()), ["rust", "web"]);
+
+    let tags = Tag::read_many(&pool, &[sql.id, web.id]).await.unwrap();
+    assert_eq!(labels(&tags), ["sql", "web"]);
+}
@@ -0,0 +1,92 @@
+use derive_crud::{
+    registered_entities, registered_entities_for, registered_schemas, ColumnMeta, Create, Delete,
+    Entity, EntityDerives, Read, Schema, Update,
+};
+
+#[derive(Read, Schema)]
+#[crud_table("timestamp_table")]
+struct Timestamped {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_created_at]
+    created_at: String,
+    #[crud_updated_at]
+    updated_at: String,
+}
+
+#[derive(Read)]
+#[crud_table("composite_table")]
+struct Membership {
+    #[crud_id]
+    user_id: i64,
+    #[crud_id]
+    role_id: i64,
+    note: Option<String>,
+}
+
+#[derive(Create, Update, Delete)]
+#[crud_table("client_id_table")]
+struct Client {
+    #[crud_id(generated = "client")]
+    id: String,
+    name: String,
+}
+
+fn main() {
+    let entities = registered_entities();
+    assert_eq!(entities.len(), 3);
+    assert_eq!(entities[0].name, "Client");
+    assert_eq!(entities[1].name, "Membership");
+    assert_eq!(entities[2].name, "Timestamped");
+
+    let membership = registered_entities_for("composite_table")[0];
+    assert_eq!(membership, <Membership as Entity>::META);
+    assert_eq!(membership.id_columns, &["user_id", "role_id"]);
+    assert_eq!(membership.id_type, "(i64, i64)");
+    assert_eq!(
+        membership.column("note"),
+        Some(&ColumnMeta {
+            name: "note",
+            rust_type: "Option<String>",
+            nullable: true,
+            primary_key: false,
+        })
+    );
+
+    // The derives of a struct are merged into one description.
+    let timestamped = registered_entities_for("timestamp_table")[0];
+    assert_eq!(timestamped.flags.created_at, Some("created_at"));
+    assert_eq!(timestamped.flags.updated_at, Some("updated_at"));
+    assert_eq!(timestamped.flags.version, None);
+    assert!(!timestamped.flags.client_generated_id);
+    assert!(timestamped.column("id").unwrap().primary_key);
+    assert_eq!(
+        timestamped.derives,
+        EntityDerives {
+            read: true,
+            schema: true,
+            ..EntityDerives::default()
+        }
+    );
+    assert_eq!(timestamped.schema, Some(&Timestamped::SCHEMA));
+
+    // Structs which do not derive `Read` are registered as well.
+    let client = registered_entities_for("client_id_table")[0];
+    assert!(client.flags.client_generated_id);
+    assert_eq!(client.id_type, "String");
+    assert_eq!(
+        client.derives,
+        EntityDerives {
+            create: true,
+            update: true,
+            delete: true,
+            ..EntityDerives::default()
+        }
+    );
+    assert_eq!(client.schema, None);
+
+    // The schema registry reads the same descriptions.
+    let schemas: Vec<_> = registered_schemas().collect();
+    assert_eq!(schemas, [&Timestamped::SCHEMA]);
+}
@@ -0,0 +1,42 @@
+use derive_crud::{Create, Delete, Read, Update};
+use futures_util::StreamExt;
+
+#[derive(Create, Read, Update, Delete)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+    let before = Customer::read_all(&pool).await.unwrap().len();
+
+    let mut tx = pool.begin().await.unwrap();
+    let mut debbie = Customer::create(&mut *tx, "Debbie".to_string()).await.unwrap();
+    debbie.name = "Deborah".to_string();
+    debbie.update(&mut *tx).await.unwrap();
+
+    let streamed: Vec<_> = Customer::read(&mut *tx, debbie.id).collect().await;
+    assert_eq!(streamed.len(), 1);
+    assert_eq!(
+        Customer::read_one(&mut *tx, debbie.id).await.unwrap().name,
+        "Deborah"
+    );
+    assert_eq!(Customer::read_all(&mut *tx).await.unwrap().len(), before + 1);
+    tx.rollback().await.unwrap();
+
+    assert_eq!(Customer::read_all(&pool).await.unwrap().len(), before);
+
+    let mut tx = pool.begin().await.unwrap();
+    let bob = Customer::create(&mut *tx, "Bob".to_string()).await.unwrap();
+    tx.commit().await.unwrap();
+    assert!(Customer::read_one(&pool, bob.id).await.is_ok());
+
+    let mut connection = pool.acquire().await.unwrap();
+    Customer::delete(&mut *connection, bob.id).await.unwrap();
+    assert!(Customer::read_one(&mut *connection, bob.id).await.is_err());
+}
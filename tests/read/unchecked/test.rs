@@ -0,0 +1,64 @@
+use derive_crud::{Create, Delete, Read, Update};
+use futures_util::StreamExt;
+
+#[derive(Create, Read, Update, Delete, sqlx::FromRow)]
+#[crud_table("customer_table", checked = false)]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+#[derive(Create, Read, sqlx::FromRow)]
+#[crud_table("order_table", checked = false)]
+struct Order {
+    #[crud_id]
+    id: i64,
+    #[crud_belongs_to(Customer)]
+    customer_id: i64,
+    #[crud_belongs_to(Customer, inverse = "reviewed_orders")]
+    reviewer_id: Option<i64>,
+    item: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let mut debbie = Customer::create(&pool, "Debbie".to_string()).await.unwrap();
+    let bob = Customer::create(&pool, "Bob".to_string()).await.unwrap();
+
+    debbie.name = "Deborah".to_string();
+    debbie.update(&pool).await.unwrap();
+    assert_eq!(
+        Customer::read_one(&pool, debbie.id).await.unwrap().name,
+        "Deborah"
+    );
+
+    let streamed: Vec<_> = Customer::read(&pool, bob.id).collect().await;
+    assert_eq!(streamed.len(), 1);
+    assert_eq!(
+        Customer::read_many(&pool, &[debbie.id, bob.id])
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let order = Order::create(&pool, debbie.id, Some(bob.id), "Book".to_string())
+        .await
+        .unwrap();
+    assert_eq!(order.customer(&pool).await.unwrap().name, "Deborah");
+    assert_eq!(order.reviewer(&pool).await.unwrap().unwrap().name, "Bob");
+    assert_eq!(bob.reviewed_orders(&pool).await.unwrap()[0].item, "Book");
+
+    let carol = Customer::create(&pool, "Carol".to_string()).await.unwrap();
+    Customer::delete(&pool, carol.id).await.unwrap();
+    assert!(Customer::read_one(&pool, carol.id).await.is_err());
+    assert!(Customer::read_all(&pool)
+        .await
+        .unwrap()
+        .iter()
+        .any(|customer| customer.id == bob.id));
+}
@@ -1,36 +1,147 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
 use crud_test::database_test;
+use sqlx::SqlitePool;
+
+/// Held by the tests while they set up the trybuild project and run their
+/// cases, as every test builds its cases in the same project.
+static PROJECT: Mutex<()> = Mutex::new(());
+
+/// trybuild cases run when dropped, before the project is released.
+struct TestCases {
+    cases: trybuild::TestCases,
+    _project: MutexGuard<'static, ()>,
+}
+
+impl Deref for TestCases {
+    type Target = trybuild::TestCases;
+
+    fn deref(&self) -> &trybuild::TestCases {
+        &self.cases
+    }
+}
+
+/// Creates trybuild cases using the test database, able to build offline
+/// from the committed `.sqlx` query cache.
+///
+/// trybuild builds the cases in a separate workspace under the target
+/// directory, so the cache is copied next to its manifest, where the query
+/// macros look for it when `SQLX_OFFLINE` is set. The `DATABASE_URL` of the
+/// database, which the cases use both to check their queries and at runtime,
+/// is given to them through the `[env]` of the Cargo config of the workspace.
+fn test_cases(pool: &SqlitePool) -> TestCases {
+    build_test_cases(pool, false)
+}
+
+/// Same as [`test_cases`], checking the queries against the query cache only
+/// when `offline`.
+fn build_test_cases(pool: &SqlitePool, offline: bool) -> TestCases {
+    let project = PROJECT.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let target_dir = match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_TARGET_TMPDIR")).join(".."),
+    };
+    let project_dir = target_dir.join("tests/trybuild/derive-crud");
+    let project_cache = project_dir.join(".sqlx");
+    std::fs::create_dir_all(&project_cache).unwrap();
+
+    let query_cache = Path::new(env!("CARGO_MANIFEST_DIR")).join(".sqlx");
+    for entry in std::fs::read_dir(query_cache).unwrap() {
+        let entry = entry.unwrap();
+        std::fs::copy(entry.path(), project_cache.join(entry.file_name())).unwrap();
+    }
+
+    let database_url = database_url(pool);
+    let config = format!(
+        "[env]\n\
+         DATABASE_URL = {{ value = {database_url:?}, force = true }}\n\
+         SQLX_OFFLINE = {{ value = \"{offline}\", force = true }}\n"
+    );
+    std::fs::create_dir_all(project_dir.join(".cargo")).unwrap();
+    std::fs::write(project_dir.join(".cargo/config.toml"), config).unwrap();
+
+    // The query macros do not tell Cargo about the variables they read, so
+    // the fingerprints of the cases built online are removed for the cases to
+    // be expanded again. trybuild builds them for the target triple.
+    if offline {
+        let built = target_dir.join("tests/trybuild");
+        for dir in std::fs::read_dir(built).into_iter().flatten().flatten() {
+            let fingerprints = dir.path().join("debug/.fingerprint");
+            for fingerprint in std::fs::read_dir(fingerprints)
+                .into_iter()
+                .flatten()
+                .flatten()
+            {
+                let name = fingerprint.file_name();
+                if name.to_string_lossy().starts_with("derive-crud-tests-") {
+                    std::fs::remove_dir_all(fingerprint.path()).unwrap();
+                }
+            }
+        }
+    }
+
+    TestCases {
+        cases: trybuild::TestCases::new(),
+        _project: project,
+    }
+}
 
-#[database_test]
-fn create() {
-    let t = trybuild::TestCases::new();
+/// Returns the `DATABASE_URL` of the test database.
+fn database_url(pool: &SqlitePool) -> String {
+    format!(
+        "sqlite://{}",
+        pool.connect_options().get_filename().display()
+    )
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn create(pool: SqlitePool) {
+    let t = test_cases(&pool);
     t.pass("tests/create/expected/test.rs");
-    t.compile_fail("tests/create/attribute_id_multiple/test.rs");
+    t.pass("tests/create/composite_id/test.rs");
+    t.pass("tests/create/client_id/test.rs");
+    t.pass("tests/create/client_id_generator/test.rs");
+    t.pass("tests/create/timestamps/test.rs");
     t.compile_fail("tests/create/attribute_id_none/test.rs");
     t.compile_fail("tests/create/attribute_table_multiple/test.rs");
     t.compile_fail("tests/create/attribute_table_no_name/test.rs");
     t.compile_fail("tests/create/attribute_table_none/test.rs");
     t.compile_fail("tests/create/attribute_table_nonexistant/test.rs");
     t.compile_fail("tests/create/bad_schema/test.rs");
+    t.compile_fail("tests/create/attribute_created_at_multiple/test.rs");
+    t.compile_fail("tests/create/attribute_id_bad_generation/test.rs");
 }
 
-#[database_test]
-fn read() {
-    let t = trybuild::TestCases::new();
+#[database_test(schema = "tests/schema.sql")]
+fn read(pool: SqlitePool) {
+    let t = test_cases(&pool);
     t.pass("tests/read/expected/test.rs");
-    t.compile_fail("tests/read/attribute_id_multiple/test.rs");
+    t.pass("tests/read/composite_id/test.rs");
+    t.pass("tests/read/belongs_to/test.rs");
+    t.pass("tests/read/many_to_many/test.rs");
+    t.pass("tests/read/registry/test.rs");
+    t.pass("tests/read/unchecked/test.rs");
+    t.pass("tests/read/transaction/test.rs");
     t.compile_fail("tests/read/attribute_id_none/test.rs");
     t.compile_fail("tests/read/attribute_table_multiple/test.rs");
     t.compile_fail("tests/read/attribute_table_no_name/test.rs");
     t.compile_fail("tests/read/attribute_table_none/test.rs");
     t.compile_fail("tests/read/attribute_table_nonexistant/test.rs");
     t.compile_fail("tests/read/bad_schema/test.rs");
+    t.compile_fail("tests/read/attribute_table_bad_option/test.rs");
 }
 
-#[database_test]
-fn update() {
-    let t = trybuild::TestCases::new();
+#[database_test(schema = "tests/schema.sql")]
+fn update(pool: SqlitePool) {
+    let t = test_cases(&pool);
     t.pass("tests/update/expected/test.rs");
-    t.compile_fail("tests/update/attribute_id_multiple/test.rs");
+    t.pass("tests/update/composite_id/test.rs");
+    t.pass("tests/update/timestamps/test.rs");
+    t.pass("tests/update/missing_row/test.rs");
+    t.pass("tests/update/version/test.rs");
     t.compile_fail("tests/update/attribute_id_none/test.rs");
     t.compile_fail("tests/update/attribute_table_multiple/test.rs");
     t.compile_fail("tests/update/attribute_table_no_name/test.rs");
@@ -39,15 +150,200 @@ fn update() {
     t.compile_fail("tests/update/bad_schema/test.rs");
 }
 
-#[database_test]
-fn delete() {
-    let t = trybuild::TestCases::new();
+#[database_test(schema = "tests/schema.sql")]
+fn delete(pool: SqlitePool) {
+    let t = test_cases(&pool);
     t.pass("tests/delete/expected/test.rs");
+    t.pass("tests/delete/composite_id/test.rs");
     t.pass("tests/delete/bad_schema/test.rs");
-    t.compile_fail("tests/delete/attribute_id_multiple/test.rs");
     t.compile_fail("tests/delete/attribute_id_none/test.rs");
     t.compile_fail("tests/delete/attribute_table_multiple/test.rs");
     t.compile_fail("tests/delete/attribute_table_no_name/test.rs");
     t.compile_fail("tests/delete/attribute_table_none/test.rs");
     t.compile_fail("tests/delete/attribute_table_nonexistant/test.rs");
 }
+
+#[database_test(schema = "tests/schema.sql")]
+fn schema(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/schema/expected/test.rs");
+    t.compile_fail("tests/schema/unknown_type/test.rs");
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn migration(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/migration/expected/test.rs");
+    t.pass("tests/migration/constraints/test.rs");
+}
+
+#[cfg(feature = "cli")]
+#[database_test(schema = "tests/schema.sql")]
+fn cli(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/cli/expected/test.rs");
+}
+
+#[cfg(feature = "cli")]
+#[database_test(schema = "tests/schema.sql")]
+fn cli_without_schemas(pool: SqlitePool) {
+    // No struct deriving `Schema` is linked into the test binary.
+    let url = database_url(&pool);
+    let args = ["migrate", "--database-url", &url].map(String::from);
+    let error = derive_crud::cli::run(args).await.unwrap_err();
+    assert!(error.starts_with("No struct deriving `Schema` is linked into this binary"));
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn factory(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/factory/expected/test.rs");
+    t.compile_fail("tests/factory/attribute_bad_option/test.rs");
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn hooks(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/hooks/expected/test.rs");
+    t.compile_fail("tests/hooks/attribute_bad_option/test.rs");
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn validation(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/validation/expected/test.rs");
+    t.compile_fail("tests/validation/attribute_bad_regex/test.rs");
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn audit(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/audit/expected/test.rs");
+    t.compile_fail("tests/audit/attribute_no_table/test.rs");
+}
+
+#[cfg(feature = "outbox-consumer")]
+#[database_test(schema = "tests/schema.sql")]
+fn outbox(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/outbox/expected/test.rs");
+    t.compile_fail("tests/outbox/attribute_no_table/test.rs");
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn json(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    #[cfg(feature = "page")]
+    t.pass("tests/json/page/test.rs");
+    t.pass("tests/json/expected/test.rs");
+    t.compile_fail("tests/json/attribute_id/test.rs");
+    t.compile_fail("tests/json/bad_column/test.rs");
+}
+
+#[cfg(feature = "axum")]
+#[database_test(schema = "tests/schema.sql")]
+fn axum(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/axum/expected/test.rs");
+}
+
+#[cfg(feature = "graphql")]
+#[database_test(schema = "tests/schema.sql")]
+fn graphql(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/graphql/expected/test.rs");
+    t.compile_fail("tests/graphql/attribute_bad_option/test.rs");
+}
+
+#[cfg(feature = "mock")]
+#[database_test(schema = "tests/schema.sql")]
+fn mock(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/mock/expected/test.rs");
+}
+
+#[cfg(feature = "tracing")]
+#[database_test(schema = "tests/schema.sql")]
+fn tracing(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/tracing/expected/test.rs");
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn observer(pool: SqlitePool) {
+    let t = test_cases(&pool);
+    t.pass("tests/observer/expected/test.rs");
+}
+
+#[database_test(schema = "tests/schema.sql")]
+fn offline(pool: SqlitePool) {
+    let t = build_test_cases(&pool, true);
+    t.pass("tests/create/expected/test.rs");
+    t.pass("tests/read/expected/test.rs");
+    t.pass("tests/update/expected/test.rs");
+    t.pass("tests/delete/expected/test.rs");
+    t.pass("tests/read/unchecked/test.rs");
+}
+
+#[database_test(
+    migrations = "tests/migrations",
+    fixtures("tests/fixtures/customers.sql")
+)]
+fn database_setup(pool: SqlitePool) {
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM customer_table ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(names, ["Debbie", "Bob"]);
+}
+
+#[database_test(
+    rollback,
+    migrations = "tests/migrations",
+    fixtures("tests/fixtures/customers.sql")
+)]
+fn database_rollback_insert(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) {
+    sqlx::query("INSERT INTO customer_table (name) VALUES ('Alice')")
+        .execute(&mut **tx)
+        .await
+        .unwrap();
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM customer_table")
+        .fetch_one(&mut **tx)
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+}
+
+#[database_test(
+    rollback,
+    migrations = "tests/migrations",
+    fixtures("tests/fixtures/customers.sql")
+)]
+fn database_rollback_delete(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) {
+    sqlx::query("DELETE FROM customer_table")
+        .execute(&mut **tx)
+        .await
+        .unwrap();
+
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM customer_table")
+        .fetch_all(&mut **tx)
+        .await
+        .unwrap();
+    assert!(names.is_empty());
+}
+
+#[database_test(
+    rollback,
+    migrations = "tests/migrations",
+    fixtures("tests/fixtures/customers.sql")
+)]
+fn database_rollback_fixtures(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) {
+    // Runs before, between or after the tests above, which must not leak
+    // their changes into the shared database.
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM customer_table ORDER BY id")
+        .fetch_all(&mut **tx)
+        .await
+        .unwrap();
+    assert_eq!(names, ["Debbie", "Bob"]);
+}
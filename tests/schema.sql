@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS test_table (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS timestamp_table (id INTEGER PRIMARY KEY, name TEXT NOT NULL, created_at TEXT NOT NULL, updated_at TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS versioned_table (id INTEGER PRIMARY KEY, name TEXT NOT NULL, version INTEGER NOT NULL);
+CREATE TABLE IF NOT EXISTS composite_table (user_id INTEGER NOT NULL, role_id INTEGER NOT NULL, note TEXT NOT NULL, PRIMARY KEY (user_id, role_id));
+CREATE TABLE IF NOT EXISTS client_id_table (id TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS customer_table (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS order_table (id INTEGER PRIMARY KEY, customer_id INTEGER NOT NULL REFERENCES customer_table(id), reviewer_id INTEGER REFERENCES customer_table(id), item TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS post_table (id INTEGER PRIMARY KEY, title TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS tag_table (id INTEGER PRIMARY KEY, label TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS post_tags (post_id INTEGER NOT NULL REFERENCES post_table(id), tag_id INTEGER NOT NULL REFERENCES tag_table(id), PRIMARY KEY (post_id, tag_id));
+CREATE TABLE IF NOT EXISTS member_table (id INTEGER PRIMARY KEY, email TEXT NOT NULL UNIQUE, name TEXT NOT NULL, age INTEGER NOT NULL, active BOOLEAN NOT NULL, nickname TEXT);
+CREATE TABLE IF NOT EXISTS audit_log (id INTEGER PRIMARY KEY, table_name TEXT NOT NULL, entity_id TEXT NOT NULL, operation TEXT NOT NULL, before TEXT, after TEXT, actor TEXT, created_at TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS outbox (id INTEGER PRIMARY KEY AUTOINCREMENT, table_name TEXT NOT NULL, entity_id TEXT NOT NULL, operation TEXT NOT NULL, payload TEXT NOT NULL, created_at TEXT NOT NULL, acknowledged_at TEXT);
+CREATE TABLE IF NOT EXISTS profile_table (id INTEGER PRIMARY KEY, name TEXT NOT NULL, tags TEXT NOT NULL, settings TEXT);
@@ -0,0 +1,66 @@
+use derive_crud::{Read, Schema};
+
+#[derive(Read, Schema)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+#[derive(Schema)]
+#[crud_table("schema_table")]
+struct Account {
+    #[crud_id]
+    id: i64,
+    #[crud_column(unique)]
+    email: String,
+    #[crud_column(default = "0", check = "balance >= 0")]
+    balance: f64,
+    #[crud_belongs_to(Customer)]
+    customer_id: i64,
+    #[crud_column(sql_type = "TEXT")]
+    nickname: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    assert_eq!(
+        <Account as derive_crud::Schema>::SCHEMA.create_table_sql(),
+        "CREATE TABLE IF NOT EXISTS \"schema_table\" (\
+         id INTEGER PRIMARY KEY NOT NULL, \
+         email TEXT NOT NULL UNIQUE, \
+         balance REAL NOT NULL DEFAULT 0 CHECK (balance >= 0), \
+         customer_id INTEGER NOT NULL REFERENCES customer_table(id), \
+         nickname TEXT)"
+    );
+    assert_eq!(
+        <Account as derive_crud::Schema>::SCHEMA.drop_table_sql(),
+        "DROP TABLE IF EXISTS \"schema_table\""
+    );
+
+    Account::create_table(&pool).await.unwrap();
+    sqlx::query("INSERT INTO customer_table (id, name) VALUES (1, 'Debbie')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO schema_table (email, customer_id) VALUES ('a@example.com', 1)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(
+        sqlx::query("INSERT INTO schema_table (email, customer_id) VALUES ('a@example.com', 1)")
+            .execute(&pool)
+            .await
+            .is_err()
+    );
+
+    Account::drop_table(&pool).await.unwrap();
+    assert!(sqlx::query("SELECT * FROM schema_table")
+        .execute(&pool)
+        .await
+        .is_err());
+}
@@ -0,0 +1,16 @@
+use derive_crud::Schema;
+
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+#[derive(Schema)]
+#[crud_table("schema_table")]
+struct Shape {
+    #[crud_id]
+    id: i64,
+    origin: Point,
+}
+
+fn main() {}
@@ -0,0 +1,138 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use derive_crud::tracing::field::{Field, Visit};
+use derive_crud::tracing::span::{Attributes, Id, Record};
+use derive_crud::tracing::{Event, Metadata, Subscriber};
+use derive_crud::{Create, Delete, Read, Update};
+use futures_util::StreamExt;
+use tracing_core::span::Current;
+
+#[derive(Create, Read, Update, Delete, Debug)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+/// Name, metadata and fields of every span, indexed by their ID minus one.
+type Spans = Arc<Mutex<Vec<(&'static Metadata<'static>, BTreeMap<String, String>)>>>;
+
+thread_local! {
+    /// Spans entered on the current thread, innermost last.
+    static ENTERED: RefCell<Vec<Id>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Subscriber keeping the fields of every span.
+struct Recorder {
+    spans: Spans,
+}
+
+struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name().to_string(), format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = BTreeMap::new();
+        span.record(&mut FieldVisitor(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((span.metadata(), fields));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut FieldVisitor(fields));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        ENTERED.with(|entered| entered.borrow_mut().push(span.clone()));
+    }
+
+    fn exit(&self, _: &Id) {
+        ENTERED.with(|entered| entered.borrow_mut().pop());
+    }
+
+    fn current_span(&self) -> Current {
+        let spans = self.spans.lock().unwrap();
+        ENTERED.with(|entered| match entered.borrow().last() {
+            Some(span) => Current::new(span.clone(), spans[span.into_u64() as usize - 1].0),
+            None => Current::none(),
+        })
+    }
+}
+
+fn field(spans: &Spans, name: &str, key: &str) -> Option<String> {
+    let spans = spans.lock().unwrap();
+    spans
+        .iter()
+        .rev()
+        .find(|(metadata, _)| metadata.name() == name)
+        .and_then(|(_, fields)| fields.get(key).cloned())
+}
+
+#[tokio::main]
+async fn main() {
+    let spans = Spans::default();
+    derive_crud::tracing::subscriber::set_global_default(Recorder {
+        spans: spans.clone(),
+    })
+    .unwrap();
+
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let mut debbie = Customer::create(&pool, "Debbie".to_string()).await.unwrap();
+    assert_eq!(field(&spans, "Customer::create", "entity").as_deref(), Some("Customer"));
+    assert_eq!(field(&spans, "Customer::create", "table").as_deref(), Some("customer_table"));
+    assert_eq!(field(&spans, "Customer::create", "operation").as_deref(), Some("create"));
+    assert_eq!(field(&spans, "Customer::create", "id"), Some(debbie.id.to_string()));
+    assert_eq!(field(&spans, "Customer::create", "rows_affected").as_deref(), Some("1"));
+
+    Customer::read_one(&pool, debbie.id).await.unwrap();
+    assert_eq!(field(&spans, "Customer::read_one", "id"), Some(debbie.id.to_string()));
+
+    let all = Customer::read_all(&pool).await.unwrap();
+    assert_eq!(
+        field(&spans, "Customer::read_all", "rows_affected"),
+        Some(all.len().to_string())
+    );
+
+    let streamed: Vec<_> = Customer::read(&pool, debbie.id).collect().await;
+    assert_eq!(streamed.len(), 1);
+    assert_eq!(field(&spans, "Customer::read", "rows_affected").as_deref(), Some("1"));
+
+    debbie.update(&pool).await.unwrap();
+    assert_eq!(field(&spans, "Customer::update", "rows_affected").as_deref(), Some("1"));
+
+    Customer::delete(&pool, debbie.id).await.unwrap();
+    assert_eq!(field(&spans, "Customer::delete", "rows_affected").as_deref(), Some("1"));
+
+    let error = Customer::read_one(&pool, debbie.id).await.unwrap_err();
+    assert_eq!(
+        field(&spans, "Customer::read_one", "error"),
+        Some(format!("{:?}", error.kind()))
+    );
+    assert_eq!(field(&spans, "Customer::read_one", "rows_affected"), None);
+}
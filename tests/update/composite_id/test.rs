@@ -0,0 +1,32 @@
+use derive_crud::{Create, Read, Update};
+
+#[derive(Create, Read, Update)]
+#[crud_table("composite_table")]
+struct Test {
+    #[crud_id]
+    user_id: i64,
+    #[crud_id]
+    role_id: i64,
+    note: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    Test::create(&pool, 4, 1, "first".to_string()).await.unwrap();
+    Test::create(&pool, 4, 2, "second".to_string()).await.unwrap();
+
+    Test {
+        user_id: 4,
+        role_id: 2,
+        note: "updated".to_string(),
+    }
+    .update(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(Test::read_one(&pool, (4, 1)).await.unwrap().note, "first");
+    assert_eq!(Test::read_one(&pool, (4, 2)).await.unwrap().note, "updated");
+}
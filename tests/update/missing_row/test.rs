@@ -0,0 +1,44 @@
+use derive_crud::{CRUDErrorKind, Update};
+
+#[derive(Update)]
+#[crud_table("test_table")]
+struct Test {
+    #[crud_id]
+    id: i64,
+    name: String,
+}
+
+#[derive(Update)]
+#[crud_table("timestamp_table")]
+struct Timestamped {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_created_at]
+    created_at: String,
+    #[crud_updated_at]
+    updated_at: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    // A missing row is reported whether or not columns are managed.
+    let mut missing = Test {
+        id: -1,
+        name: "Nobody".to_string(),
+    };
+    let error = missing.update(&pool).await.unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::NotFound);
+
+    let mut missing = Timestamped {
+        id: -1,
+        name: "Nobody".to_string(),
+        created_at: String::new(),
+        updated_at: String::new(),
+    };
+    let error = missing.update(&pool).await.unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::NotFound);
+}
@@ -0,0 +1,39 @@
+use derive_crud::{Create, Update};
+
+#[derive(Create, Update)]
+#[crud_table("timestamp_table")]
+struct Test {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_created_at]
+    created_at: String,
+    #[crud_updated_at]
+    updated_at: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let mut item = Test::create(&pool, "Debbie".to_string()).await.unwrap();
+    let created_at = item.created_at.clone();
+
+    item.name = "Deborah".to_string();
+    item.created_at = String::new();
+    item.updated_at = String::new();
+    item.update(&pool).await.unwrap();
+
+    assert_eq!(item.name, "Deborah");
+    assert_eq!(item.created_at, created_at);
+    assert!(!item.updated_at.is_empty());
+
+    let mut missing = Test {
+        id: -1,
+        name: "Nobody".to_string(),
+        created_at: String::new(),
+        updated_at: String::new(),
+    };
+    assert!(missing.update(&pool).await.is_err());
+}
@@ -0,0 +1,35 @@
+use derive_crud::{CRUDErrorKind, Create, Update};
+
+#[derive(Create, Update)]
+#[crud_table("versioned_table")]
+struct Test {
+    #[crud_id]
+    id: i64,
+    name: String,
+    #[crud_version]
+    version: i64,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let mut first = Test::create(&pool, "Debbie".to_string()).await.unwrap();
+    assert_eq!(first.version, 1);
+
+    let mut second = Test {
+        id: first.id,
+        name: first.name.clone(),
+        version: first.version,
+    };
+
+    first.name = "Deborah".to_string();
+    first.update(&pool).await.unwrap();
+    assert_eq!(first.version, 2);
+
+    second.name = "Debra".to_string();
+    let error = second.update(&pool).await.unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::Conflict);
+    assert_eq!(second.version, 1);
+}
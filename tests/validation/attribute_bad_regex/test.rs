@@ -0,0 +1,12 @@
+use derive_crud::Create;
+
+#[derive(Create)]
+#[crud_table("customer_table")]
+struct Customer {
+    #[crud_id]
+    id: i64,
+    #[crud_validate(regex = "[a-z")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,86 @@
+use derive_crud::{CRUDErrorKind, Create, Read, Update};
+
+fn not_reserved(name: &String) -> Result<(), String> {
+    match name.as_str() {
+        "admin" => Err("is reserved".to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Create, Read, Update, Debug)]
+#[crud_table("member_table")]
+struct Member {
+    #[crud_id]
+    id: i64,
+    #[crud_validate(email, length(max = 32))]
+    email: String,
+    #[crud_validate(length(min = 1, max = 16), custom = not_reserved)]
+    name: String,
+    #[crud_validate(range(min = 0, max = 150))]
+    age: i64,
+    active: bool,
+    #[crud_validate(regex = "^[a-z]+$")]
+    nickname: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let pool = sqlx::SqlitePool::connect(&database_url).await.unwrap();
+
+    let mut debbie = Member::create(
+        &pool,
+        "debbie@example.com".to_string(),
+        "Debbie".to_string(),
+        47,
+        true,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Every failing field is listed, and nothing is written.
+    let error = Member::create(
+        &pool,
+        "not an email".to_string(),
+        "admin".to_string(),
+        200,
+        true,
+        Some("Debbie2".to_string()),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::Validation);
+    let failed: Vec<_> = error
+        .field_errors()
+        .iter()
+        .map(|error| (error.field, error.rule))
+        .collect();
+    assert_eq!(
+        failed,
+        vec![
+            ("email", "email"),
+            ("name", "custom"),
+            ("age", "range"),
+            ("nickname", "regex"),
+        ]
+    );
+    assert_eq!(error.field_errors()[1].message, "is reserved");
+    assert_eq!(error.field_errors()[2].message, "must be between 0 and 150");
+    assert_eq!(Member::read_all(&pool).await.unwrap().len(), 1);
+
+    debbie.name = String::new();
+    debbie.nickname = Some("deb".to_string());
+    let error = debbie.update(&pool).await.unwrap_err();
+    assert_eq!(error.kind(), CRUDErrorKind::Validation);
+    assert_eq!(error.field_errors().len(), 1);
+    assert_eq!(error.field_errors()[0].field, "name");
+    assert_eq!(error.field_errors()[0].message, "length must be between 1 and 16");
+    assert_eq!(
+        Member::read_one(&pool, debbie.id).await.unwrap().name,
+        "Debbie"
+    );
+
+    debbie.name = "Deborah".to_string();
+    debbie.update(&pool).await.unwrap();
+}